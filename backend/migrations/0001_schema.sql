@@ -19,13 +19,13 @@ $$ language 'plpgsql';
 -- ============================================================================
 
 -- Campaigns table
-CREATE TABLE campaigns (
+CREATE TABLE IF NOT EXISTS campaigns (
     id SERIAL PRIMARY KEY,
     name TEXT NOT NULL,
     setting TEXT,
     themes TEXT[] DEFAULT '{}',
     player_characters JSONB DEFAULT '[]',
-    status TEXT DEFAULT 'created' CHECK (status IN ('created', 'generating', 'completed', 'error')),
+    status TEXT DEFAULT 'created' CHECK (status IN ('created', 'generating', 'completed', 'error', 'cancelled')),
     generation_phase TEXT,
     phase_progress INTEGER DEFAULT 0,
     total_phases INTEGER DEFAULT 9, -- Updated for 9-phase system
@@ -42,19 +42,19 @@ CREATE TABLE campaigns (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_campaigns_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_campaigns_updated_at BEFORE UPDATE
     ON campaigns FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
-CREATE INDEX idx_campaigns_status ON campaigns(status);
-CREATE INDEX idx_campaigns_created_at ON campaigns(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_campaigns_status ON campaigns(status);
+CREATE INDEX IF NOT EXISTS idx_campaigns_created_at ON campaigns(created_at DESC);
 
 -- ============================================================================
 -- Phase 1A: Core World Systems
 -- ============================================================================
 
 -- Time & Calendar Systems
-CREATE TABLE calendar_systems (
+CREATE TABLE IF NOT EXISTS calendar_systems (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
@@ -72,12 +72,12 @@ CREATE TABLE calendar_systems (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_calendar_systems_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_calendar_systems_updated_at BEFORE UPDATE
     ON calendar_systems FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
 -- Planes of Existence
-CREATE TABLE planes (
+CREATE TABLE IF NOT EXISTS planes (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
@@ -93,12 +93,12 @@ CREATE TABLE planes (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_planes_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_planes_updated_at BEFORE UPDATE
     ON planes FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
 -- Geography & Regions (hierarchical)
-CREATE TABLE geography_regions (
+CREATE TABLE IF NOT EXISTS geography_regions (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
@@ -118,15 +118,15 @@ CREATE TABLE geography_regions (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_geography_regions_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_geography_regions_updated_at BEFORE UPDATE
     ON geography_regions FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
-CREATE INDEX idx_geography_regions_campaign_id ON geography_regions(campaign_id);
-CREATE INDEX idx_geography_regions_parent ON geography_regions(parent_region_id);
+CREATE INDEX IF NOT EXISTS idx_geography_regions_campaign_id ON geography_regions(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_geography_regions_parent ON geography_regions(parent_region_id);
 
 -- Historical Timeline
-CREATE TABLE historical_periods (
+CREATE TABLE IF NOT EXISTS historical_periods (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     period_name TEXT NOT NULL,
@@ -144,12 +144,12 @@ CREATE TABLE historical_periods (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_historical_periods_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_historical_periods_updated_at BEFORE UPDATE
     ON historical_periods FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
 -- Economic Systems
-CREATE TABLE economic_systems (
+CREATE TABLE IF NOT EXISTS economic_systems (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     region_id INTEGER REFERENCES geography_regions(id),
@@ -166,12 +166,12 @@ CREATE TABLE economic_systems (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_economic_systems_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_economic_systems_updated_at BEFORE UPDATE
     ON economic_systems FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
 -- Legal Systems
-CREATE TABLE legal_systems (
+CREATE TABLE IF NOT EXISTS legal_systems (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     region_id INTEGER REFERENCES geography_regions(id),
@@ -186,12 +186,12 @@ CREATE TABLE legal_systems (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_legal_systems_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_legal_systems_updated_at BEFORE UPDATE
     ON legal_systems FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
 -- Astronomy & Zodiac
-CREATE TABLE celestial_bodies (
+CREATE TABLE IF NOT EXISTS celestial_bodies (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
@@ -206,7 +206,7 @@ CREATE TABLE celestial_bodies (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_celestial_bodies_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_celestial_bodies_updated_at BEFORE UPDATE
     ON celestial_bodies FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
@@ -215,7 +215,7 @@ CREATE TRIGGER update_celestial_bodies_updated_at BEFORE UPDATE
 -- ============================================================================
 
 -- Races & Subraces
-CREATE TABLE races (
+CREATE TABLE IF NOT EXISTS races (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
@@ -235,15 +235,15 @@ CREATE TABLE races (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_races_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_races_updated_at BEFORE UPDATE
     ON races FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
-CREATE INDEX idx_races_campaign_id ON races(campaign_id);
-CREATE INDEX idx_races_parent ON races(parent_race_id);
+CREATE INDEX IF NOT EXISTS idx_races_campaign_id ON races(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_races_parent ON races(parent_race_id);
 
 -- Classes & Subclasses
-CREATE TABLE character_classes (
+CREATE TABLE IF NOT EXISTS character_classes (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
@@ -263,15 +263,15 @@ CREATE TABLE character_classes (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_character_classes_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_character_classes_updated_at BEFORE UPDATE
     ON character_classes FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
-CREATE INDEX idx_character_classes_campaign_id ON character_classes(campaign_id);
-CREATE INDEX idx_character_classes_parent ON character_classes(parent_class_id);
+CREATE INDEX IF NOT EXISTS idx_character_classes_campaign_id ON character_classes(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_character_classes_parent ON character_classes(parent_class_id);
 
 -- Feats
-CREATE TABLE feats (
+CREATE TABLE IF NOT EXISTS feats (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
@@ -285,12 +285,12 @@ CREATE TABLE feats (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_feats_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_feats_updated_at BEFORE UPDATE
     ON feats FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
 -- Backgrounds
-CREATE TABLE backgrounds (
+CREATE TABLE IF NOT EXISTS backgrounds (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
@@ -307,7 +307,7 @@ CREATE TABLE backgrounds (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_backgrounds_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_backgrounds_updated_at BEFORE UPDATE
     ON backgrounds FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
@@ -316,7 +316,7 @@ CREATE TRIGGER update_backgrounds_updated_at BEFORE UPDATE
 -- ============================================================================
 
 -- Languages
-CREATE TABLE languages (
+CREATE TABLE IF NOT EXISTS languages (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
@@ -331,17 +331,17 @@ CREATE TABLE languages (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_languages_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_languages_updated_at BEFORE UPDATE
     ON languages FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
 -- Cultures
-CREATE TABLE cultures (
+CREATE TABLE IF NOT EXISTS cultures (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
-    primary_race_id INTEGER REFERENCES races(id),
-    geography_region_id INTEGER REFERENCES geography_regions(id),
+    primary_race_id INTEGER REFERENCES races(id) ON DELETE SET NULL,
+    geography_region_id INTEGER REFERENCES geography_regions(id) ON DELETE SET NULL,
     values JSONB DEFAULT '[]',
     traditions JSONB DEFAULT '[]',
     social_structure TEXT,
@@ -356,12 +356,12 @@ CREATE TABLE cultures (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_cultures_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_cultures_updated_at BEFORE UPDATE
     ON cultures FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
 -- Basic Factions
-CREATE TABLE factions (
+CREATE TABLE IF NOT EXISTS factions (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
@@ -381,12 +381,12 @@ CREATE TABLE factions (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_factions_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_factions_updated_at BEFORE UPDATE
     ON factions FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
 -- Pantheons
-CREATE TABLE pantheons (
+CREATE TABLE IF NOT EXISTS pantheons (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
@@ -400,12 +400,12 @@ CREATE TABLE pantheons (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_pantheons_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_pantheons_updated_at BEFORE UPDATE
     ON pantheons FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
 -- Deities
-CREATE TABLE deities (
+CREATE TABLE IF NOT EXISTS deities (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     pantheon_id INTEGER REFERENCES pantheons(id),
@@ -426,7 +426,7 @@ CREATE TABLE deities (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_deities_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_deities_updated_at BEFORE UPDATE
     ON deities FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
@@ -435,14 +435,14 @@ CREATE TRIGGER update_deities_updated_at BEFORE UPDATE
 -- ============================================================================
 
 -- Core Entities (replaces NPCs table)
-CREATE TABLE entities (
+CREATE TABLE IF NOT EXISTS entities (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
     entity_type TEXT NOT NULL, -- pc, npc, creature, monster
-    race_id INTEGER REFERENCES races(id),
-    class_id INTEGER REFERENCES character_classes(id),
-    background_id INTEGER REFERENCES backgrounds(id),
+    race_id INTEGER REFERENCES races(id) ON DELETE SET NULL,
+    class_id INTEGER REFERENCES character_classes(id) ON DELETE SET NULL,
+    background_id INTEGER REFERENCES backgrounds(id) ON DELETE SET NULL,
     level_or_cr TEXT, -- "5" for level 5, "1/4" for CR 1/4
     ability_scores JSONB DEFAULT '{}', -- STR, DEX, CON, INT, WIS, CHA
     hit_points INTEGER,
@@ -472,26 +472,26 @@ CREATE TABLE entities (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_entities_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_entities_updated_at BEFORE UPDATE
     ON entities FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
-CREATE INDEX idx_entities_campaign_id ON entities(campaign_id);
-CREATE INDEX idx_entities_type ON entities(entity_type);
-CREATE INDEX idx_entities_race ON entities(race_id);
+CREATE INDEX IF NOT EXISTS idx_entities_campaign_id ON entities(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_entities_type ON entities(entity_type);
+CREATE INDEX IF NOT EXISTS idx_entities_race ON entities(race_id);
 
 -- ============================================================================
 -- Phase 2B: Hierarchical Locations System
 -- ============================================================================
 
 -- Core Locations (with hierarchy)
-CREATE TABLE locations (
+CREATE TABLE IF NOT EXISTS locations (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
     location_type TEXT NOT NULL, -- city, district, building, dungeon, wilderness, etc.
     parent_location_id INTEGER REFERENCES locations(id),
-    geography_region_id INTEGER REFERENCES geography_regions(id),
+    geography_region_id INTEGER REFERENCES geography_regions(id) ON DELETE SET NULL,
     description TEXT,
     population INTEGER,
     government_type TEXT,
@@ -507,16 +507,16 @@ CREATE TABLE locations (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_locations_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_locations_updated_at BEFORE UPDATE
     ON locations FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
-CREATE INDEX idx_locations_campaign_id ON locations(campaign_id);
-CREATE INDEX idx_locations_parent ON locations(parent_location_id);
-CREATE INDEX idx_locations_type ON locations(location_type);
+CREATE INDEX IF NOT EXISTS idx_locations_campaign_id ON locations(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_locations_parent ON locations(parent_location_id);
+CREATE INDEX IF NOT EXISTS idx_locations_type ON locations(location_type);
 
 -- Dungeons (special location type)
-CREATE TABLE dungeons (
+CREATE TABLE IF NOT EXISTS dungeons (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     location_id INTEGER NOT NULL REFERENCES locations(id) ON DELETE CASCADE,
@@ -535,7 +535,7 @@ CREATE TABLE dungeons (
 );
 
 -- Buildings (special location type)
-CREATE TABLE buildings (
+CREATE TABLE IF NOT EXISTS buildings (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     location_id INTEGER NOT NULL REFERENCES locations(id) ON DELETE CASCADE,
@@ -551,12 +551,12 @@ CREATE TABLE buildings (
 );
 
 -- Shops
-CREATE TABLE shops (
+CREATE TABLE IF NOT EXISTS shops (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     building_id INTEGER NOT NULL REFERENCES buildings(id) ON DELETE CASCADE,
     shop_type TEXT NOT NULL,
-    owner_entity_id INTEGER REFERENCES entities(id),
+    owner_entity_id INTEGER REFERENCES entities(id) ON DELETE SET NULL,
     specialties TEXT[],
     inventory_level TEXT,
     price_modifier NUMERIC(3,2) DEFAULT 1.0,
@@ -566,11 +566,11 @@ CREATE TABLE shops (
 );
 
 -- Taverns
-CREATE TABLE taverns (
+CREATE TABLE IF NOT EXISTS taverns (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     building_id INTEGER NOT NULL REFERENCES buildings(id) ON DELETE CASCADE,
-    owner_entity_id INTEGER REFERENCES entities(id),
+    owner_entity_id INTEGER REFERENCES entities(id) ON DELETE SET NULL,
     atmosphere TEXT,
     specialties TEXT[],
     room_rates JSONB DEFAULT '{}',
@@ -581,12 +581,12 @@ CREATE TABLE taverns (
 );
 
 -- Temples
-CREATE TABLE temples (
+CREATE TABLE IF NOT EXISTS temples (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     building_id INTEGER NOT NULL REFERENCES buildings(id) ON DELETE CASCADE,
-    deity_id INTEGER REFERENCES deities(id),
-    high_priest_entity_id INTEGER REFERENCES entities(id),
+    deity_id INTEGER REFERENCES deities(id) ON DELETE SET NULL,
+    high_priest_entity_id INTEGER REFERENCES entities(id) ON DELETE SET NULL,
     services TEXT[],
     holy_days JSONB DEFAULT '[]',
     relics TEXT[],
@@ -600,7 +600,7 @@ CREATE TABLE temples (
 -- ============================================================================
 
 -- Comprehensive Items
-CREATE TABLE items (
+CREATE TABLE IF NOT EXISTS items (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     name TEXT NOT NULL,
@@ -623,16 +623,16 @@ CREATE TABLE items (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_items_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_items_updated_at BEFORE UPDATE
     ON items FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
-CREATE INDEX idx_items_campaign_id ON items(campaign_id);
-CREATE INDEX idx_items_type ON items(item_type);
-CREATE INDEX idx_items_rarity ON items(rarity);
+CREATE INDEX IF NOT EXISTS idx_items_campaign_id ON items(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_items_type ON items(item_type);
+CREATE INDEX IF NOT EXISTS idx_items_rarity ON items(rarity);
 
 -- Item Effects & Abilities
-CREATE TABLE item_effects (
+CREATE TABLE IF NOT EXISTS item_effects (
     id SERIAL PRIMARY KEY,
     item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
     effect_type TEXT NOT NULL, -- spell, ability, passive, curse
@@ -650,7 +650,7 @@ CREATE TABLE item_effects (
 );
 
 -- Sentient Item Properties
-CREATE TABLE sentient_item_properties (
+CREATE TABLE IF NOT EXISTS sentient_item_properties (
     id SERIAL PRIMARY KEY,
     item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
     intelligence INTEGER NOT NULL,
@@ -672,7 +672,7 @@ CREATE TABLE sentient_item_properties (
 -- ============================================================================
 
 -- Quest hooks table
-CREATE TABLE quest_hooks (
+CREATE TABLE IF NOT EXISTS quest_hooks (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     title TEXT NOT NULL,
@@ -691,15 +691,15 @@ CREATE TABLE quest_hooks (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_quest_hooks_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_quest_hooks_updated_at BEFORE UPDATE
     ON quest_hooks FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
-CREATE INDEX idx_quest_hooks_campaign_id ON quest_hooks(campaign_id);
-CREATE INDEX idx_quest_hooks_status ON quest_hooks(status);
+CREATE INDEX IF NOT EXISTS idx_quest_hooks_campaign_id ON quest_hooks(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_quest_hooks_status ON quest_hooks(status);
 
 -- Encounters table
-CREATE TABLE encounters (
+CREATE TABLE IF NOT EXISTS encounters (
     id SERIAL PRIMARY KEY,
     campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
     location_id INTEGER REFERENCES locations(id) ON DELETE SET NULL,
@@ -719,19 +719,19 @@ CREATE TABLE encounters (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_encounters_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_encounters_updated_at BEFORE UPDATE
     ON encounters FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
-CREATE INDEX idx_encounters_campaign_id ON encounters(campaign_id);
-CREATE INDEX idx_encounters_location_id ON encounters(location_id);
+CREATE INDEX IF NOT EXISTS idx_encounters_campaign_id ON encounters(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_encounters_location_id ON encounters(location_id);
 
 -- ============================================================================
 -- Relationship Tables (Many-to-Many)
 -- ============================================================================
 
 -- Entity Relationships
-CREATE TABLE entity_relationships (
+CREATE TABLE IF NOT EXISTS entity_relationships (
     id SERIAL PRIMARY KEY,
     entity1_id INTEGER NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
     entity2_id INTEGER NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
@@ -745,15 +745,15 @@ CREATE TABLE entity_relationships (
     CONSTRAINT different_entities CHECK (entity1_id != entity2_id)
 );
 
-CREATE TRIGGER update_entity_relationships_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_entity_relationships_updated_at BEFORE UPDATE
     ON entity_relationships FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
-CREATE INDEX idx_entity_relationships_entity1 ON entity_relationships(entity1_id);
-CREATE INDEX idx_entity_relationships_entity2 ON entity_relationships(entity2_id);
+CREATE INDEX IF NOT EXISTS idx_entity_relationships_entity1 ON entity_relationships(entity1_id);
+CREATE INDEX IF NOT EXISTS idx_entity_relationships_entity2 ON entity_relationships(entity2_id);
 
 -- Entity-Location Associations
-CREATE TABLE entity_locations (
+CREATE TABLE IF NOT EXISTS entity_locations (
     id SERIAL PRIMARY KEY,
     entity_id INTEGER NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
     location_id INTEGER NOT NULL REFERENCES locations(id) ON DELETE CASCADE,
@@ -765,15 +765,15 @@ CREATE TABLE entity_locations (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_entity_locations_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_entity_locations_updated_at BEFORE UPDATE
     ON entity_locations FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
-CREATE INDEX idx_entity_locations_entity ON entity_locations(entity_id);
-CREATE INDEX idx_entity_locations_location ON entity_locations(location_id);
+CREATE INDEX IF NOT EXISTS idx_entity_locations_entity ON entity_locations(entity_id);
+CREATE INDEX IF NOT EXISTS idx_entity_locations_location ON entity_locations(location_id);
 
 -- Entity-Faction Memberships
-CREATE TABLE entity_factions (
+CREATE TABLE IF NOT EXISTS entity_factions (
     id SERIAL PRIMARY KEY,
     entity_id INTEGER NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
     faction_id INTEGER NOT NULL REFERENCES factions(id) ON DELETE CASCADE,
@@ -786,15 +786,15 @@ CREATE TABLE entity_factions (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_entity_factions_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_entity_factions_updated_at BEFORE UPDATE
     ON entity_factions FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
-CREATE INDEX idx_entity_factions_entity ON entity_factions(entity_id);
-CREATE INDEX idx_entity_factions_faction ON entity_factions(faction_id);
+CREATE INDEX IF NOT EXISTS idx_entity_factions_entity ON entity_factions(entity_id);
+CREATE INDEX IF NOT EXISTS idx_entity_factions_faction ON entity_factions(faction_id);
 
 -- Entity-Item Ownership
-CREATE TABLE entity_items (
+CREATE TABLE IF NOT EXISTS entity_items (
     id SERIAL PRIMARY KEY,
     entity_id INTEGER NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
     item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
@@ -806,15 +806,15 @@ CREATE TABLE entity_items (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_entity_items_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_entity_items_updated_at BEFORE UPDATE
     ON entity_items FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
-CREATE INDEX idx_entity_items_entity ON entity_items(entity_id);
-CREATE INDEX idx_entity_items_item ON entity_items(item_id);
+CREATE INDEX IF NOT EXISTS idx_entity_items_entity ON entity_items(entity_id);
+CREATE INDEX IF NOT EXISTS idx_entity_items_item ON entity_items(item_id);
 
 -- Location-Item Placement
-CREATE TABLE location_items (
+CREATE TABLE IF NOT EXISTS location_items (
     id SERIAL PRIMARY KEY,
     location_id INTEGER NOT NULL REFERENCES locations(id) ON DELETE CASCADE,
     item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
@@ -827,15 +827,15 @@ CREATE TABLE location_items (
     updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE TRIGGER update_location_items_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_location_items_updated_at BEFORE UPDATE
     ON location_items FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
-CREATE INDEX idx_location_items_location ON location_items(location_id);
-CREATE INDEX idx_location_items_item ON location_items(item_id);
+CREATE INDEX IF NOT EXISTS idx_location_items_location ON location_items(location_id);
+CREATE INDEX IF NOT EXISTS idx_location_items_item ON location_items(item_id);
 
 -- Quest-Entity Relationships
-CREATE TABLE quest_entities (
+CREATE TABLE IF NOT EXISTS quest_entities (
     id SERIAL PRIMARY KEY,
     quest_hook_id INTEGER NOT NULL REFERENCES quest_hooks(id) ON DELETE CASCADE,
     entity_id INTEGER NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
@@ -845,11 +845,11 @@ CREATE TABLE quest_entities (
     created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE INDEX idx_quest_entities_quest ON quest_entities(quest_hook_id);
-CREATE INDEX idx_quest_entities_entity ON quest_entities(entity_id);
+CREATE INDEX IF NOT EXISTS idx_quest_entities_quest ON quest_entities(quest_hook_id);
+CREATE INDEX IF NOT EXISTS idx_quest_entities_entity ON quest_entities(entity_id);
 
 -- Quest-Location Relationships
-CREATE TABLE quest_locations (
+CREATE TABLE IF NOT EXISTS quest_locations (
     id SERIAL PRIMARY KEY,
     quest_hook_id INTEGER NOT NULL REFERENCES quest_hooks(id) ON DELETE CASCADE,
     location_id INTEGER NOT NULL REFERENCES locations(id) ON DELETE CASCADE,
@@ -859,11 +859,11 @@ CREATE TABLE quest_locations (
     created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE INDEX idx_quest_locations_quest ON quest_locations(quest_hook_id);
-CREATE INDEX idx_quest_locations_location ON quest_locations(location_id);
+CREATE INDEX IF NOT EXISTS idx_quest_locations_quest ON quest_locations(quest_hook_id);
+CREATE INDEX IF NOT EXISTS idx_quest_locations_location ON quest_locations(location_id);
 
 -- Faction Relationships (faction-to-faction)
-CREATE TABLE faction_relationships (
+CREATE TABLE IF NOT EXISTS faction_relationships (
     id SERIAL PRIMARY KEY,
     faction1_id INTEGER NOT NULL REFERENCES factions(id) ON DELETE CASCADE,
     faction2_id INTEGER NOT NULL REFERENCES factions(id) ON DELETE CASCADE,
@@ -876,15 +876,15 @@ CREATE TABLE faction_relationships (
     CONSTRAINT different_factions CHECK (faction1_id != faction2_id)
 );
 
-CREATE TRIGGER update_faction_relationships_updated_at BEFORE UPDATE
+CREATE OR REPLACE TRIGGER update_faction_relationships_updated_at BEFORE UPDATE
     ON faction_relationships FOR EACH ROW EXECUTE PROCEDURE 
     update_updated_at_column();
 
-CREATE INDEX idx_faction_relationships_faction1 ON faction_relationships(faction1_id);
-CREATE INDEX idx_faction_relationships_faction2 ON faction_relationships(faction2_id);
+CREATE INDEX IF NOT EXISTS idx_faction_relationships_faction1 ON faction_relationships(faction1_id);
+CREATE INDEX IF NOT EXISTS idx_faction_relationships_faction2 ON faction_relationships(faction2_id);
 
 -- Race-Culture Associations
-CREATE TABLE race_cultures (
+CREATE TABLE IF NOT EXISTS race_cultures (
     id SERIAL PRIMARY KEY,
     race_id INTEGER NOT NULL REFERENCES races(id) ON DELETE CASCADE,
     culture_id INTEGER NOT NULL REFERENCES cultures(id) ON DELETE CASCADE,
@@ -893,26 +893,26 @@ CREATE TABLE race_cultures (
     created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
 );
 
-CREATE INDEX idx_race_cultures_race ON race_cultures(race_id);
-CREATE INDEX idx_race_cultures_culture ON race_cultures(culture_id);
+CREATE INDEX IF NOT EXISTS idx_race_cultures_race ON race_cultures(race_id);
+CREATE INDEX IF NOT EXISTS idx_race_cultures_culture ON race_cultures(culture_id);
 
 -- ============================================================================
 -- Additional Indexes for Performance
 -- ============================================================================
 
 -- Composite indexes for common queries
-CREATE INDEX idx_entities_campaign_type ON entities(campaign_id, entity_type);
-CREATE INDEX idx_locations_campaign_type ON locations(campaign_id, location_type);
-CREATE INDEX idx_items_campaign_type_rarity ON items(campaign_id, item_type, rarity);
-CREATE INDEX idx_quest_hooks_campaign_status ON quest_hooks(campaign_id, status);
-CREATE INDEX idx_encounters_campaign_type ON encounters(campaign_id, encounter_type);
+CREATE INDEX IF NOT EXISTS idx_entities_campaign_type ON entities(campaign_id, entity_type);
+CREATE INDEX IF NOT EXISTS idx_locations_campaign_type ON locations(campaign_id, location_type);
+CREATE INDEX IF NOT EXISTS idx_items_campaign_type_rarity ON items(campaign_id, item_type, rarity);
+CREATE INDEX IF NOT EXISTS idx_quest_hooks_campaign_status ON quest_hooks(campaign_id, status);
+CREATE INDEX IF NOT EXISTS idx_encounters_campaign_type ON encounters(campaign_id, encounter_type);
 
 -- ============================================================================
 -- Views for Common Queries
 -- ============================================================================
 
 -- View for PC-connected NPCs with location info
-CREATE VIEW pc_connected_npcs AS
+CREATE OR REPLACE VIEW pc_connected_npcs AS
 SELECT 
     e.*,
     el.location_id,
@@ -923,7 +923,7 @@ LEFT JOIN locations l ON el.location_id = l.id
 WHERE e.entity_type = 'npc' AND e.pc_connection_type IS NOT NULL;
 
 -- View for faction hierarchies
-CREATE VIEW faction_hierarchies AS
+CREATE OR REPLACE VIEW faction_hierarchies AS
 SELECT 
     f1.id as faction_id,
     f1.name as faction_name,
@@ -936,7 +936,7 @@ JOIN faction_relationships fr ON f1.id = fr.faction1_id
 JOIN factions f2 ON fr.faction2_id = f2.id;
 
 -- View for location hierarchies
-CREATE VIEW location_hierarchies AS
+CREATE OR REPLACE VIEW location_hierarchies AS
 WITH RECURSIVE location_tree AS (
     -- Base case: top-level locations
     SELECT id, name, location_type, parent_location_id, 1 as level, 
@@ -960,8 +960,8 @@ SELECT * FROM location_tree;
 -- ============================================================================
 
 -- Indexes for new campaign_id columns to ensure fast filtering by campaign
-CREATE INDEX idx_buildings_campaign_id ON buildings(campaign_id);
-CREATE INDEX idx_dungeons_campaign_id ON dungeons(campaign_id);
-CREATE INDEX idx_shops_campaign_id ON shops(campaign_id);
-CREATE INDEX idx_taverns_campaign_id ON taverns(campaign_id);
-CREATE INDEX idx_temples_campaign_id ON temples(campaign_id);
\ No newline at end of file
+CREATE INDEX IF NOT EXISTS idx_buildings_campaign_id ON buildings(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_dungeons_campaign_id ON dungeons(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_shops_campaign_id ON shops(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_taverns_campaign_id ON taverns(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_temples_campaign_id ON temples(campaign_id);
\ No newline at end of file
@@ -0,0 +1,22 @@
+-- Track the status of every generation phase per campaign
+-- This lets a failed generation resume from the phase that failed instead of starting over
+
+CREATE TABLE IF NOT EXISTS campaign_generation_phases (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    phase_name TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'running', 'completed', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    error_message TEXT,
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT unique_campaign_phase UNIQUE (campaign_id, phase_name)
+);
+
+CREATE OR REPLACE TRIGGER update_campaign_generation_phases_updated_at BEFORE UPDATE
+    ON campaign_generation_phases FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_campaign_generation_phases_campaign_id ON campaign_generation_phases(campaign_id);
@@ -0,0 +1,47 @@
+-- Support regenerating a single phase without wiping the phases around it
+-- Downstream phases can be left in place and flagged as stale instead of being re-run
+
+ALTER TABLE campaign_generation_phases DROP CONSTRAINT IF EXISTS campaign_generation_phases_status_check;
+ALTER TABLE campaign_generation_phases ADD CONSTRAINT campaign_generation_phases_status_check
+    CHECK (status IN ('pending', 'running', 'completed', 'failed', 'stale'));
+
+-- References that cross phase boundaries must not block deleting an upstream phase's rows.
+-- Stale downstream rows keep their content and simply lose the link.
+
+-- Phase 1C -> 1A/1B
+ALTER TABLE cultures DROP CONSTRAINT IF EXISTS cultures_primary_race_id_fkey;
+ALTER TABLE cultures ADD CONSTRAINT cultures_primary_race_id_fkey
+    FOREIGN KEY (primary_race_id) REFERENCES races(id) ON DELETE SET NULL;
+ALTER TABLE cultures DROP CONSTRAINT IF EXISTS cultures_geography_region_id_fkey;
+ALTER TABLE cultures ADD CONSTRAINT cultures_geography_region_id_fkey
+    FOREIGN KEY (geography_region_id) REFERENCES geography_regions(id) ON DELETE SET NULL;
+
+-- Phase 2A -> 1B
+ALTER TABLE entities DROP CONSTRAINT IF EXISTS entities_race_id_fkey;
+ALTER TABLE entities ADD CONSTRAINT entities_race_id_fkey
+    FOREIGN KEY (race_id) REFERENCES races(id) ON DELETE SET NULL;
+ALTER TABLE entities DROP CONSTRAINT IF EXISTS entities_class_id_fkey;
+ALTER TABLE entities ADD CONSTRAINT entities_class_id_fkey
+    FOREIGN KEY (class_id) REFERENCES character_classes(id) ON DELETE SET NULL;
+ALTER TABLE entities DROP CONSTRAINT IF EXISTS entities_background_id_fkey;
+ALTER TABLE entities ADD CONSTRAINT entities_background_id_fkey
+    FOREIGN KEY (background_id) REFERENCES backgrounds(id) ON DELETE SET NULL;
+
+-- Phase 2B -> 1A
+ALTER TABLE locations DROP CONSTRAINT IF EXISTS locations_geography_region_id_fkey;
+ALTER TABLE locations ADD CONSTRAINT locations_geography_region_id_fkey
+    FOREIGN KEY (geography_region_id) REFERENCES geography_regions(id) ON DELETE SET NULL;
+
+-- Phase 3B -> 1C/2A
+ALTER TABLE shops DROP CONSTRAINT IF EXISTS shops_owner_entity_id_fkey;
+ALTER TABLE shops ADD CONSTRAINT shops_owner_entity_id_fkey
+    FOREIGN KEY (owner_entity_id) REFERENCES entities(id) ON DELETE SET NULL;
+ALTER TABLE taverns DROP CONSTRAINT IF EXISTS taverns_owner_entity_id_fkey;
+ALTER TABLE taverns ADD CONSTRAINT taverns_owner_entity_id_fkey
+    FOREIGN KEY (owner_entity_id) REFERENCES entities(id) ON DELETE SET NULL;
+ALTER TABLE temples DROP CONSTRAINT IF EXISTS temples_deity_id_fkey;
+ALTER TABLE temples ADD CONSTRAINT temples_deity_id_fkey
+    FOREIGN KEY (deity_id) REFERENCES deities(id) ON DELETE SET NULL;
+ALTER TABLE temples DROP CONSTRAINT IF EXISTS temples_high_priest_entity_id_fkey;
+ALTER TABLE temples ADD CONSTRAINT temples_high_priest_entity_id_fkey
+    FOREIGN KEY (high_priest_entity_id) REFERENCES entities(id) ON DELETE SET NULL;
@@ -0,0 +1,29 @@
+-- Durable queue for generation work
+-- Workers claim jobs under a lease; a job whose lease expires (e.g. the backend restarted
+-- mid-generation) is picked up again and resumed from the first incomplete phase
+
+CREATE TABLE IF NOT EXISTS generation_jobs (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('generate', 'resume', 'regenerate')),
+    phase_name TEXT, -- target phase for 'regenerate' jobs
+    cascade_mode TEXT CHECK (cascade_mode IN ('rerun', 'mark_stale')),
+    state TEXT NOT NULL DEFAULT 'pending' CHECK (state IN ('pending', 'running', 'completed', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 3,
+    worker_id TEXT,
+    lease_expires_at TIMESTAMPTZ,
+    error_message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_generation_jobs_updated_at BEFORE UPDATE
+    ON generation_jobs FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+-- Only one active job per campaign
+CREATE UNIQUE INDEX IF NOT EXISTS idx_generation_jobs_active_campaign
+    ON generation_jobs(campaign_id) WHERE state IN ('pending', 'running');
+
+CREATE INDEX IF NOT EXISTS idx_generation_jobs_claimable ON generation_jobs(state, lease_expires_at);
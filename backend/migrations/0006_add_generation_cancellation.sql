@@ -0,0 +1,12 @@
+-- Allow an in-flight generation to be cancelled
+-- A running job is flagged with cancel_requested; whichever worker holds it notices on its next heartbeat
+
+ALTER TABLE campaigns DROP CONSTRAINT IF EXISTS campaigns_status_check;
+ALTER TABLE campaigns ADD CONSTRAINT campaigns_status_check
+    CHECK (status IN ('created', 'generating', 'completed', 'error', 'cancelled'));
+
+ALTER TABLE generation_jobs ADD COLUMN IF NOT EXISTS cancel_requested BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE generation_jobs DROP CONSTRAINT IF EXISTS generation_jobs_state_check;
+ALTER TABLE generation_jobs ADD CONSTRAINT generation_jobs_state_check
+    CHECK (state IN ('pending', 'running', 'completed', 'failed', 'cancelled'));
@@ -0,0 +1,17 @@
+-- Rejected phase outputs that were sent back to the model for repair
+-- One row per failed attempt: what the model returned and why it was rejected
+
+CREATE TABLE IF NOT EXISTS generation_repair_attempts (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    phase_name TEXT NOT NULL,
+    attempt INTEGER NOT NULL,
+    tool_name TEXT NOT NULL,
+    tool_input JSONB NOT NULL,
+    error_message TEXT NOT NULL,
+    violations JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_generation_repair_attempts_campaign_phase
+    ON generation_repair_attempts(campaign_id, phase_name);
@@ -0,0 +1,24 @@
+-- Audit log of every LLM call made during generation: what was sent, with which parameters,
+-- and what came back, so odd campaign content can be traced to the prompt that produced it
+
+CREATE TABLE IF NOT EXISTS generation_calls (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    phase_name TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    system_prompt TEXT,
+    messages JSONB NOT NULL,
+    tool_name TEXT NOT NULL,
+    tool_schema_hash TEXT NOT NULL,
+    temperature REAL NOT NULL,
+    max_tokens INTEGER NOT NULL,
+    raw_response JSONB,
+    error_message TEXT,
+    input_tokens INTEGER NOT NULL DEFAULT 0,
+    output_tokens INTEGER NOT NULL DEFAULT 0,
+    duration_ms BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_generation_calls_campaign_phase ON generation_calls(campaign_id, phase_name);
@@ -0,0 +1,19 @@
+-- The tool input each phase was finally saved from, so a generation can be replayed into a
+-- new campaign without calling the model again
+
+CREATE TABLE IF NOT EXISTS generation_phase_outputs (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    phase_name TEXT NOT NULL,
+    tool_name TEXT NOT NULL,
+    tool_input JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (campaign_id, phase_name)
+);
+
+-- Replay jobs save another campaign's recorded outputs instead of generating
+ALTER TABLE generation_jobs DROP CONSTRAINT IF EXISTS generation_jobs_kind_check;
+ALTER TABLE generation_jobs ADD CONSTRAINT generation_jobs_kind_check
+    CHECK (kind IN ('generate', 'resume', 'regenerate', 'replay'));
+ALTER TABLE generation_jobs
+    ADD COLUMN IF NOT EXISTS source_campaign_id INTEGER REFERENCES campaigns(id) ON DELETE SET NULL;
@@ -0,0 +1,383 @@
+-- Tables the DatabaseServiceEnhanced save paths write to that the schema never defined.
+-- Columns follow the structs in models/items.rs, models/entity.rs, models/character_building.rs
+-- and models/location_enhanced.rs. Detail rows copy campaign_id from their parent item, entity
+-- or location so campaign isolation and cleanup work the same as for every other table.
+
+-- ============================================================================
+-- Character building: subraces and subclasses
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS subraces (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    race_id INTEGER NOT NULL REFERENCES races(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    description TEXT,
+    additional_traits JSONB DEFAULT '{}',
+    additional_ability_scores JSONB DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_subraces_updated_at BEFORE UPDATE
+    ON subraces FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_subraces_campaign_id ON subraces(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_subraces_race_id ON subraces(race_id);
+
+CREATE TABLE IF NOT EXISTS subclasses (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    class_id INTEGER NOT NULL REFERENCES character_classes(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    description TEXT,
+    additional_features JSONB DEFAULT '{}',
+    additional_spells JSONB DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_subclasses_updated_at BEFORE UPDATE
+    ON subclasses FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_subclasses_campaign_id ON subclasses(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_subclasses_class_id ON subclasses(class_id);
+
+CREATE TABLE IF NOT EXISTS spells (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    level INTEGER NOT NULL DEFAULT 0 CHECK (level BETWEEN 0 AND 9), -- 0 = cantrip
+    school TEXT,
+    casting_time TEXT,
+    range TEXT,
+    components TEXT[] DEFAULT '{}',
+    duration TEXT,
+    description TEXT,
+    higher_levels TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_spells_updated_at BEFORE UPDATE
+    ON spells FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_spells_campaign_id ON spells(campaign_id);
+
+-- ============================================================================
+-- Item details: one row per weapon, armor, potion, scroll or sentient item
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS weapons (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER REFERENCES campaigns(id) ON DELETE CASCADE,
+    item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    weapon_type TEXT NOT NULL DEFAULT 'simple', -- simple, martial
+    damage_dice TEXT,
+    damage_type TEXT,
+    weapon_properties TEXT[] DEFAULT '{}',
+    range_normal INTEGER,
+    range_long INTEGER,
+    finesse BOOLEAN NOT NULL DEFAULT FALSE,
+    light BOOLEAN NOT NULL DEFAULT FALSE,
+    heavy BOOLEAN NOT NULL DEFAULT FALSE,
+    reach BOOLEAN NOT NULL DEFAULT FALSE,
+    thrown BOOLEAN NOT NULL DEFAULT FALSE,
+    two_handed BOOLEAN NOT NULL DEFAULT FALSE,
+    versatile BOOLEAN NOT NULL DEFAULT FALSE,
+    ammunition TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_weapons_updated_at BEFORE UPDATE
+    ON weapons FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_weapons_campaign_id ON weapons(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_weapons_item_id ON weapons(item_id);
+
+CREATE TABLE IF NOT EXISTS armor (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER REFERENCES campaigns(id) ON DELETE CASCADE,
+    item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    armor_type TEXT NOT NULL DEFAULT 'light', -- light, medium, heavy, shield
+    armor_class INTEGER,
+    dex_modifier_cap INTEGER,
+    strength_requirement INTEGER,
+    stealth_disadvantage BOOLEAN NOT NULL DEFAULT FALSE,
+    don_time TEXT,
+    doff_time TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_armor_updated_at BEFORE UPDATE
+    ON armor FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_armor_campaign_id ON armor(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_armor_item_id ON armor(item_id);
+
+CREATE TABLE IF NOT EXISTS potions (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER REFERENCES campaigns(id) ON DELETE CASCADE,
+    item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    potion_type TEXT NOT NULL DEFAULT 'healing',
+    effect JSONB DEFAULT '{}',
+    duration TEXT,
+    onset_time TEXT,
+    side_effects JSONB DEFAULT '[]',
+    brewing_difficulty TEXT,
+    ingredients JSONB DEFAULT '[]',
+    brewing_time TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_potions_updated_at BEFORE UPDATE
+    ON potions FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_potions_campaign_id ON potions(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_potions_item_id ON potions(item_id);
+
+CREATE TABLE IF NOT EXISTS spell_scrolls (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER REFERENCES campaigns(id) ON DELETE CASCADE,
+    item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    spell_name TEXT NOT NULL,
+    spell_level INTEGER NOT NULL DEFAULT 1 CHECK (spell_level BETWEEN 0 AND 9),
+    school_of_magic TEXT,
+    casting_time TEXT,
+    range_area TEXT,
+    duration TEXT,
+    components JSONB DEFAULT '[]',
+    spell_description TEXT,
+    save_dc INTEGER,
+    attack_bonus INTEGER,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_spell_scrolls_updated_at BEFORE UPDATE
+    ON spell_scrolls FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_spell_scrolls_campaign_id ON spell_scrolls(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_spell_scrolls_item_id ON spell_scrolls(item_id);
+
+CREATE TABLE IF NOT EXISTS sentient_items (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER REFERENCES campaigns(id) ON DELETE CASCADE,
+    item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+    intelligence INTEGER NOT NULL DEFAULT 10,
+    wisdom INTEGER NOT NULL DEFAULT 10,
+    charisma INTEGER NOT NULL DEFAULT 10,
+    alignment TEXT,
+    personality_traits JSONB DEFAULT '[]',
+    ideals JSONB DEFAULT '[]',
+    bonds JSONB DEFAULT '[]',
+    flaws JSONB DEFAULT '[]',
+    languages TEXT[] DEFAULT '{}',
+    senses JSONB DEFAULT '[]',
+    communication_method TEXT,
+    ego_score INTEGER,
+    purpose TEXT,
+    special_purpose TEXT,
+    conflict_resolution JSONB DEFAULT '{}',
+    backstory TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_sentient_items_updated_at BEFORE UPDATE
+    ON sentient_items FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_sentient_items_campaign_id ON sentient_items(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_sentient_items_item_id ON sentient_items(item_id);
+
+-- ============================================================================
+-- Entity details: creatures, flora and fauna
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS creatures (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER REFERENCES campaigns(id) ON DELETE CASCADE,
+    entity_id INTEGER NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
+    creature_type TEXT NOT NULL DEFAULT 'beast',
+    size TEXT DEFAULT 'medium',
+    alignment TEXT,
+    armor_class INTEGER,
+    hit_points INTEGER,
+    hit_dice TEXT,
+    speed JSONB DEFAULT '{}',
+    ability_scores JSONB DEFAULT '{}',
+    saving_throws JSONB DEFAULT '{}',
+    skills JSONB DEFAULT '{}',
+    damage_resistances JSONB DEFAULT '[]',
+    damage_immunities JSONB DEFAULT '[]',
+    condition_immunities JSONB DEFAULT '[]',
+    senses JSONB DEFAULT '{}',
+    languages TEXT[] DEFAULT '{}',
+    challenge_rating REAL NOT NULL DEFAULT 1,
+    proficiency_bonus INTEGER,
+    actions JSONB DEFAULT '[]',
+    legendary_actions JSONB DEFAULT '[]',
+    lair_actions JSONB DEFAULT '[]',
+    regional_effects JSONB DEFAULT '[]',
+    habitat JSONB DEFAULT '[]',
+    diet TEXT,
+    behavior JSONB DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_creatures_updated_at BEFORE UPDATE
+    ON creatures FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_creatures_campaign_id ON creatures(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_creatures_entity_id ON creatures(entity_id);
+
+CREATE TABLE IF NOT EXISTS flora (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER REFERENCES campaigns(id) ON DELETE CASCADE,
+    entity_id INTEGER NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
+    plant_type TEXT NOT NULL DEFAULT 'plant',
+    size TEXT,
+    rarity TEXT DEFAULT 'common',
+    habitat JSONB DEFAULT '[]',
+    growing_conditions JSONB DEFAULT '{}',
+    appearance TEXT,
+    uses JSONB DEFAULT '[]',
+    magical_properties JSONB DEFAULT '{}',
+    harvest_difficulty TEXT,
+    seasonal_availability JSONB DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_flora_updated_at BEFORE UPDATE
+    ON flora FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_flora_campaign_id ON flora(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_flora_entity_id ON flora(entity_id);
+
+CREATE TABLE IF NOT EXISTS fauna (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER REFERENCES campaigns(id) ON DELETE CASCADE,
+    entity_id INTEGER NOT NULL REFERENCES entities(id) ON DELETE CASCADE,
+    animal_type TEXT,
+    size TEXT,
+    habitat JSONB DEFAULT '[]',
+    diet TEXT,
+    behavior JSONB DEFAULT '{}',
+    social_structure TEXT,
+    appearance TEXT,
+    abilities JSONB DEFAULT '[]',
+    domestication_status TEXT,
+    rarity TEXT DEFAULT 'common',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_fauna_updated_at BEFORE UPDATE
+    ON fauna FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_fauna_campaign_id ON fauna(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_fauna_entity_id ON fauna(entity_id);
+
+-- ============================================================================
+-- World details: lore, weather, encounter tables and travel routes
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS lore_entries (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    category TEXT DEFAULT 'general', -- history, legend, religion, secret, etc.
+    content TEXT,
+    is_public BOOLEAN NOT NULL DEFAULT FALSE, -- common knowledge vs. discovered in play
+    related_entities INTEGER[] DEFAULT '{}',
+    related_locations INTEGER[] DEFAULT '{}',
+    related_items INTEGER[] DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_lore_entries_updated_at BEFORE UPDATE
+    ON lore_entries FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_lore_entries_campaign_id ON lore_entries(campaign_id);
+
+CREATE TABLE IF NOT EXISTS weather_patterns (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    region_id INTEGER REFERENCES geography_regions(id) ON DELETE SET NULL,
+    season TEXT NOT NULL DEFAULT 'spring',
+    typical_conditions TEXT[] DEFAULT '{}',
+    temperature_range_low INTEGER,
+    temperature_range_high INTEGER,
+    precipitation_chance INTEGER DEFAULT 30 CHECK (precipitation_chance BETWEEN 0 AND 100),
+    extreme_weather_events JSONB DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_weather_patterns_updated_at BEFORE UPDATE
+    ON weather_patterns FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_weather_patterns_campaign_id ON weather_patterns(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_weather_patterns_region_id ON weather_patterns(region_id);
+
+CREATE TABLE IF NOT EXISTS random_encounter_tables (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    environment_type TEXT,
+    level_range_min INTEGER NOT NULL DEFAULT 1,
+    level_range_max INTEGER NOT NULL DEFAULT 20,
+    encounters JSONB DEFAULT '[]', -- [{roll, description, ...}]
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_random_encounter_tables_updated_at BEFORE UPDATE
+    ON random_encounter_tables FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_random_encounter_tables_campaign_id ON random_encounter_tables(campaign_id);
+
+CREATE TABLE IF NOT EXISTS location_connections (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER REFERENCES campaigns(id) ON DELETE CASCADE,
+    from_location_id INTEGER NOT NULL REFERENCES locations(id) ON DELETE CASCADE,
+    to_location_id INTEGER NOT NULL REFERENCES locations(id) ON DELETE CASCADE,
+    connection_type TEXT NOT NULL DEFAULT 'road',
+    distance REAL,
+    travel_time TEXT,
+    difficulty TEXT DEFAULT 'normal',
+    description TEXT,
+    hazards JSONB DEFAULT '[]',
+    landmarks JSONB DEFAULT '[]',
+    cost INTEGER,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE OR REPLACE TRIGGER update_location_connections_updated_at BEFORE UPDATE
+    ON location_connections FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_location_connections_campaign_id ON location_connections(campaign_id);
+CREATE INDEX IF NOT EXISTS idx_location_connections_from ON location_connections(from_location_id);
+CREATE INDEX IF NOT EXISTS idx_location_connections_to ON location_connections(to_location_id);
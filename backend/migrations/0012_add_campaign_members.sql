@@ -0,0 +1,37 @@
+-- Who can reach a campaign through Hasura: the GMs who run it and the players in it
+-- user_id is the X-Hasura-User-Id of the signed-in user
+
+ALTER TABLE campaigns ADD COLUMN IF NOT EXISTS created_by TEXT;
+
+CREATE TABLE IF NOT EXISTS campaign_members (
+    id SERIAL PRIMARY KEY,
+    campaign_id INTEGER NOT NULL REFERENCES campaigns(id) ON DELETE CASCADE,
+    user_id TEXT NOT NULL,
+    role TEXT NOT NULL DEFAULT 'player' CHECK (role IN ('gm', 'player')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (campaign_id, user_id)
+);
+
+CREATE OR REPLACE TRIGGER update_campaign_members_updated_at BEFORE UPDATE
+    ON campaign_members FOR EACH ROW EXECUTE PROCEDURE
+    update_updated_at_column();
+
+CREATE INDEX IF NOT EXISTS idx_campaign_members_user_id ON campaign_members(user_id);
+
+-- Whoever creates a campaign runs it
+CREATE OR REPLACE FUNCTION add_campaign_creator_as_gm()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.created_by IS NOT NULL THEN
+        INSERT INTO campaign_members (campaign_id, user_id, role)
+        VALUES (NEW.id, NEW.created_by, 'gm')
+        ON CONFLICT (campaign_id, user_id) DO UPDATE SET role = 'gm';
+    END IF;
+    RETURN NEW;
+END;
+$$ language 'plpgsql';
+
+CREATE OR REPLACE TRIGGER add_campaign_creator_as_gm AFTER INSERT
+    ON campaigns FOR EACH ROW EXECUTE PROCEDURE
+    add_campaign_creator_as_gm();
@@ -0,0 +1,124 @@
+-- 0011 left campaign_id and the defaulted columns of the content detail tables nullable,
+-- although the structs in models/ read them as plain values. Every save path fills
+-- campaign_id from the parent row and either binds or defaults the rest, so make them
+-- NOT NULL. Columns the save paths may leave empty stay nullable and are Option in the structs.
+
+-- ============================================================================
+-- campaign_id, copied from the parent item, entity or location
+-- ============================================================================
+
+UPDATE weapons d SET campaign_id = i.campaign_id FROM items i WHERE d.campaign_id IS NULL AND i.id = d.item_id;
+UPDATE armor d SET campaign_id = i.campaign_id FROM items i WHERE d.campaign_id IS NULL AND i.id = d.item_id;
+UPDATE potions d SET campaign_id = i.campaign_id FROM items i WHERE d.campaign_id IS NULL AND i.id = d.item_id;
+UPDATE spell_scrolls d SET campaign_id = i.campaign_id FROM items i WHERE d.campaign_id IS NULL AND i.id = d.item_id;
+UPDATE sentient_items d SET campaign_id = i.campaign_id FROM items i WHERE d.campaign_id IS NULL AND i.id = d.item_id;
+UPDATE creatures d SET campaign_id = e.campaign_id FROM entities e WHERE d.campaign_id IS NULL AND e.id = d.entity_id;
+UPDATE flora d SET campaign_id = e.campaign_id FROM entities e WHERE d.campaign_id IS NULL AND e.id = d.entity_id;
+UPDATE fauna d SET campaign_id = e.campaign_id FROM entities e WHERE d.campaign_id IS NULL AND e.id = d.entity_id;
+UPDATE location_connections d SET campaign_id = l.campaign_id FROM locations l WHERE d.campaign_id IS NULL AND l.id = d.from_location_id;
+
+ALTER TABLE weapons ALTER COLUMN campaign_id SET NOT NULL;
+ALTER TABLE armor ALTER COLUMN campaign_id SET NOT NULL;
+ALTER TABLE potions ALTER COLUMN campaign_id SET NOT NULL;
+ALTER TABLE spell_scrolls ALTER COLUMN campaign_id SET NOT NULL;
+ALTER TABLE sentient_items ALTER COLUMN campaign_id SET NOT NULL;
+ALTER TABLE creatures ALTER COLUMN campaign_id SET NOT NULL;
+ALTER TABLE flora ALTER COLUMN campaign_id SET NOT NULL;
+ALTER TABLE fauna ALTER COLUMN campaign_id SET NOT NULL;
+ALTER TABLE location_connections ALTER COLUMN campaign_id SET NOT NULL;
+
+-- ============================================================================
+-- Defaulted columns
+-- ============================================================================
+
+UPDATE subraces SET additional_traits = COALESCE(additional_traits, '{}'),
+    additional_ability_scores = COALESCE(additional_ability_scores, '{}');
+ALTER TABLE subraces
+    ALTER COLUMN additional_traits SET NOT NULL,
+    ALTER COLUMN additional_ability_scores SET NOT NULL;
+
+UPDATE subclasses SET additional_features = COALESCE(additional_features, '{}'),
+    additional_spells = COALESCE(additional_spells, '{}');
+ALTER TABLE subclasses
+    ALTER COLUMN additional_features SET NOT NULL,
+    ALTER COLUMN additional_spells SET NOT NULL;
+
+UPDATE weapons SET weapon_properties = COALESCE(weapon_properties, '{}');
+ALTER TABLE weapons ALTER COLUMN weapon_properties SET NOT NULL;
+
+UPDATE potions SET effect = COALESCE(effect, '{}'), side_effects = COALESCE(side_effects, '[]'),
+    ingredients = COALESCE(ingredients, '[]');
+ALTER TABLE potions
+    ALTER COLUMN effect SET NOT NULL,
+    ALTER COLUMN side_effects SET NOT NULL,
+    ALTER COLUMN ingredients SET NOT NULL;
+
+UPDATE spell_scrolls SET components = COALESCE(components, '[]');
+ALTER TABLE spell_scrolls ALTER COLUMN components SET NOT NULL;
+
+UPDATE sentient_items SET personality_traits = COALESCE(personality_traits, '[]'), ideals = COALESCE(ideals, '[]'),
+    bonds = COALESCE(bonds, '[]'), flaws = COALESCE(flaws, '[]'), languages = COALESCE(languages, '{}'),
+    senses = COALESCE(senses, '[]'), communication_method = COALESCE(communication_method, 'telepathy'),
+    conflict_resolution = COALESCE(conflict_resolution, '{}');
+ALTER TABLE sentient_items
+    ALTER COLUMN personality_traits SET NOT NULL,
+    ALTER COLUMN ideals SET NOT NULL,
+    ALTER COLUMN bonds SET NOT NULL,
+    ALTER COLUMN flaws SET NOT NULL,
+    ALTER COLUMN languages SET NOT NULL,
+    ALTER COLUMN senses SET NOT NULL,
+    ALTER COLUMN communication_method SET DEFAULT 'telepathy',
+    ALTER COLUMN communication_method SET NOT NULL,
+    ALTER COLUMN conflict_resolution SET NOT NULL;
+
+UPDATE creatures SET size = COALESCE(size, 'medium'), speed = COALESCE(speed, '{}'),
+    ability_scores = COALESCE(ability_scores, '{}'), saving_throws = COALESCE(saving_throws, '{}'),
+    skills = COALESCE(skills, '{}'), damage_resistances = COALESCE(damage_resistances, '[]'),
+    damage_immunities = COALESCE(damage_immunities, '[]'), condition_immunities = COALESCE(condition_immunities, '[]'),
+    senses = COALESCE(senses, '{}'), languages = COALESCE(languages, '{}'), actions = COALESCE(actions, '[]'),
+    legendary_actions = COALESCE(legendary_actions, '[]'), lair_actions = COALESCE(lair_actions, '[]'),
+    regional_effects = COALESCE(regional_effects, '[]'), habitat = COALESCE(habitat, '[]'),
+    behavior = COALESCE(behavior, '{}');
+ALTER TABLE creatures
+    ALTER COLUMN size SET NOT NULL,
+    ALTER COLUMN speed SET NOT NULL,
+    ALTER COLUMN ability_scores SET NOT NULL,
+    ALTER COLUMN saving_throws SET NOT NULL,
+    ALTER COLUMN skills SET NOT NULL,
+    ALTER COLUMN damage_resistances SET NOT NULL,
+    ALTER COLUMN damage_immunities SET NOT NULL,
+    ALTER COLUMN condition_immunities SET NOT NULL,
+    ALTER COLUMN senses SET NOT NULL,
+    ALTER COLUMN languages SET NOT NULL,
+    ALTER COLUMN actions SET NOT NULL,
+    ALTER COLUMN legendary_actions SET NOT NULL,
+    ALTER COLUMN lair_actions SET NOT NULL,
+    ALTER COLUMN regional_effects SET NOT NULL,
+    ALTER COLUMN habitat SET NOT NULL,
+    ALTER COLUMN behavior SET NOT NULL;
+
+UPDATE flora SET rarity = COALESCE(rarity, 'common'), habitat = COALESCE(habitat, '[]'),
+    growing_conditions = COALESCE(growing_conditions, '{}'), uses = COALESCE(uses, '[]'),
+    magical_properties = COALESCE(magical_properties, '{}'), seasonal_availability = COALESCE(seasonal_availability, '[]');
+ALTER TABLE flora
+    ALTER COLUMN rarity SET NOT NULL,
+    ALTER COLUMN habitat SET NOT NULL,
+    ALTER COLUMN growing_conditions SET NOT NULL,
+    ALTER COLUMN uses SET NOT NULL,
+    ALTER COLUMN magical_properties SET NOT NULL,
+    ALTER COLUMN seasonal_availability SET NOT NULL;
+
+UPDATE fauna SET habitat = COALESCE(habitat, '[]'), behavior = COALESCE(behavior, '{}'),
+    abilities = COALESCE(abilities, '[]'), rarity = COALESCE(rarity, 'common');
+ALTER TABLE fauna
+    ALTER COLUMN habitat SET NOT NULL,
+    ALTER COLUMN behavior SET NOT NULL,
+    ALTER COLUMN abilities SET NOT NULL,
+    ALTER COLUMN rarity SET NOT NULL;
+
+UPDATE location_connections SET difficulty = COALESCE(difficulty, 'normal'), hazards = COALESCE(hazards, '[]'),
+    landmarks = COALESCE(landmarks, '[]');
+ALTER TABLE location_connections
+    ALTER COLUMN difficulty SET NOT NULL,
+    ALTER COLUMN hazards SET NOT NULL,
+    ALTER COLUMN landmarks SET NOT NULL;
@@ -0,0 +1,86 @@
+-- Regenerating a phase with mark_stale leaves its dependents' rows in place. References from
+-- those rows into the regenerated phase's tables cascaded on delete, so clearing 2A, 2B or 2C
+-- silently deleted 3A/3B/3C content. Null the reference instead; the dependent phase is marked
+-- stale and a later regeneration replaces the row.
+-- References within a single phase (buildings -> locations, weapons -> items, ...) still cascade.
+
+-- 3B -> 2B
+ALTER TABLE shops ALTER COLUMN building_id DROP NOT NULL;
+ALTER TABLE shops DROP CONSTRAINT IF EXISTS shops_building_id_fkey;
+ALTER TABLE shops ADD CONSTRAINT shops_building_id_fkey
+    FOREIGN KEY (building_id) REFERENCES buildings(id) ON DELETE SET NULL;
+
+ALTER TABLE taverns ALTER COLUMN building_id DROP NOT NULL;
+ALTER TABLE taverns DROP CONSTRAINT IF EXISTS taverns_building_id_fkey;
+ALTER TABLE taverns ADD CONSTRAINT taverns_building_id_fkey
+    FOREIGN KEY (building_id) REFERENCES buildings(id) ON DELETE SET NULL;
+
+ALTER TABLE temples ALTER COLUMN building_id DROP NOT NULL;
+ALTER TABLE temples DROP CONSTRAINT IF EXISTS temples_building_id_fkey;
+ALTER TABLE temples ADD CONSTRAINT temples_building_id_fkey
+    FOREIGN KEY (building_id) REFERENCES buildings(id) ON DELETE SET NULL;
+
+-- 3C -> 1C, 2A, 2B, 2C and 3A
+ALTER TABLE entity_relationships ALTER COLUMN entity1_id DROP NOT NULL, ALTER COLUMN entity2_id DROP NOT NULL;
+ALTER TABLE entity_relationships DROP CONSTRAINT IF EXISTS entity_relationships_entity1_id_fkey;
+ALTER TABLE entity_relationships ADD CONSTRAINT entity_relationships_entity1_id_fkey
+    FOREIGN KEY (entity1_id) REFERENCES entities(id) ON DELETE SET NULL;
+ALTER TABLE entity_relationships DROP CONSTRAINT IF EXISTS entity_relationships_entity2_id_fkey;
+ALTER TABLE entity_relationships ADD CONSTRAINT entity_relationships_entity2_id_fkey
+    FOREIGN KEY (entity2_id) REFERENCES entities(id) ON DELETE SET NULL;
+
+ALTER TABLE entity_locations ALTER COLUMN entity_id DROP NOT NULL, ALTER COLUMN location_id DROP NOT NULL;
+ALTER TABLE entity_locations DROP CONSTRAINT IF EXISTS entity_locations_entity_id_fkey;
+ALTER TABLE entity_locations ADD CONSTRAINT entity_locations_entity_id_fkey
+    FOREIGN KEY (entity_id) REFERENCES entities(id) ON DELETE SET NULL;
+ALTER TABLE entity_locations DROP CONSTRAINT IF EXISTS entity_locations_location_id_fkey;
+ALTER TABLE entity_locations ADD CONSTRAINT entity_locations_location_id_fkey
+    FOREIGN KEY (location_id) REFERENCES locations(id) ON DELETE SET NULL;
+
+ALTER TABLE entity_factions ALTER COLUMN entity_id DROP NOT NULL, ALTER COLUMN faction_id DROP NOT NULL;
+ALTER TABLE entity_factions DROP CONSTRAINT IF EXISTS entity_factions_entity_id_fkey;
+ALTER TABLE entity_factions ADD CONSTRAINT entity_factions_entity_id_fkey
+    FOREIGN KEY (entity_id) REFERENCES entities(id) ON DELETE SET NULL;
+ALTER TABLE entity_factions DROP CONSTRAINT IF EXISTS entity_factions_faction_id_fkey;
+ALTER TABLE entity_factions ADD CONSTRAINT entity_factions_faction_id_fkey
+    FOREIGN KEY (faction_id) REFERENCES factions(id) ON DELETE SET NULL;
+
+ALTER TABLE faction_relationships ALTER COLUMN faction1_id DROP NOT NULL, ALTER COLUMN faction2_id DROP NOT NULL;
+ALTER TABLE faction_relationships DROP CONSTRAINT IF EXISTS faction_relationships_faction1_id_fkey;
+ALTER TABLE faction_relationships ADD CONSTRAINT faction_relationships_faction1_id_fkey
+    FOREIGN KEY (faction1_id) REFERENCES factions(id) ON DELETE SET NULL;
+ALTER TABLE faction_relationships DROP CONSTRAINT IF EXISTS faction_relationships_faction2_id_fkey;
+ALTER TABLE faction_relationships ADD CONSTRAINT faction_relationships_faction2_id_fkey
+    FOREIGN KEY (faction2_id) REFERENCES factions(id) ON DELETE SET NULL;
+
+ALTER TABLE entity_items ALTER COLUMN entity_id DROP NOT NULL, ALTER COLUMN item_id DROP NOT NULL;
+ALTER TABLE entity_items DROP CONSTRAINT IF EXISTS entity_items_entity_id_fkey;
+ALTER TABLE entity_items ADD CONSTRAINT entity_items_entity_id_fkey
+    FOREIGN KEY (entity_id) REFERENCES entities(id) ON DELETE SET NULL;
+ALTER TABLE entity_items DROP CONSTRAINT IF EXISTS entity_items_item_id_fkey;
+ALTER TABLE entity_items ADD CONSTRAINT entity_items_item_id_fkey
+    FOREIGN KEY (item_id) REFERENCES items(id) ON DELETE SET NULL;
+
+ALTER TABLE location_items ALTER COLUMN location_id DROP NOT NULL, ALTER COLUMN item_id DROP NOT NULL;
+ALTER TABLE location_items DROP CONSTRAINT IF EXISTS location_items_location_id_fkey;
+ALTER TABLE location_items ADD CONSTRAINT location_items_location_id_fkey
+    FOREIGN KEY (location_id) REFERENCES locations(id) ON DELETE SET NULL;
+ALTER TABLE location_items DROP CONSTRAINT IF EXISTS location_items_item_id_fkey;
+ALTER TABLE location_items ADD CONSTRAINT location_items_item_id_fkey
+    FOREIGN KEY (item_id) REFERENCES items(id) ON DELETE SET NULL;
+
+ALTER TABLE quest_entities ALTER COLUMN quest_hook_id DROP NOT NULL, ALTER COLUMN entity_id DROP NOT NULL;
+ALTER TABLE quest_entities DROP CONSTRAINT IF EXISTS quest_entities_quest_hook_id_fkey;
+ALTER TABLE quest_entities ADD CONSTRAINT quest_entities_quest_hook_id_fkey
+    FOREIGN KEY (quest_hook_id) REFERENCES quest_hooks(id) ON DELETE SET NULL;
+ALTER TABLE quest_entities DROP CONSTRAINT IF EXISTS quest_entities_entity_id_fkey;
+ALTER TABLE quest_entities ADD CONSTRAINT quest_entities_entity_id_fkey
+    FOREIGN KEY (entity_id) REFERENCES entities(id) ON DELETE SET NULL;
+
+ALTER TABLE quest_locations ALTER COLUMN quest_hook_id DROP NOT NULL, ALTER COLUMN location_id DROP NOT NULL;
+ALTER TABLE quest_locations DROP CONSTRAINT IF EXISTS quest_locations_quest_hook_id_fkey;
+ALTER TABLE quest_locations ADD CONSTRAINT quest_locations_quest_hook_id_fkey
+    FOREIGN KEY (quest_hook_id) REFERENCES quest_hooks(id) ON DELETE SET NULL;
+ALTER TABLE quest_locations DROP CONSTRAINT IF EXISTS quest_locations_location_id_fkey;
+ALTER TABLE quest_locations ADD CONSTRAINT quest_locations_location_id_fkey
+    FOREIGN KEY (location_id) REFERENCES locations(id) ON DELETE SET NULL;
@@ -0,0 +1,43 @@
+use dnd_campaign_generator::services::{check_schema_drift, collect_sql_statements, read_known_drift, SqlSchema};
+use std::path::Path;
+
+/// Report tables and columns the services' SQL and GraphQL inserts use that the schema does
+/// not define.
+///
+/// Compares against `backend/migrations` by default, or against the live database at
+/// `DATABASE_URL` with `--database`. Drift listed in `known_schema_drift.txt` is left out
+/// unless `--strict` is given. Exits non-zero when anything else is missing.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let use_database = std::env::args().any(|arg| arg == "--database");
+    let strict = std::env::args().any(|arg| arg == "--strict");
+
+    let schema = if use_database {
+        dotenvy::dotenv().ok();
+        let url = std::env::var("DATABASE_URL")?;
+        let pool = sqlx::PgPool::connect(&url).await?;
+        SqlSchema::from_database(&pool).await?
+    } else {
+        SqlSchema::from_migrations(manifest_dir.join("migrations"))?
+    };
+
+    let statements = collect_sql_statements(manifest_dir.join("src"))?;
+    let known = if strict { Default::default() } else { read_known_drift(manifest_dir.join("known_schema_drift.txt"))? };
+    let drift: Vec<_> = check_schema_drift(&schema, &statements)
+        .into_iter()
+        .filter(|problem| !known.contains(&problem.target()))
+        .collect();
+
+    println!("Checked {} SQL statements against the {}", statements.len(), if use_database { "database" } else { "migrations" });
+    if drift.is_empty() {
+        println!("No schema drift found");
+        return Ok(());
+    }
+
+    for problem in &drift {
+        println!("  {}", problem);
+    }
+    println!("{} schema mismatches", drift.len());
+    std::process::exit(1);
+}
@@ -1,6 +1,6 @@
 use std::env;
 use std::sync::Arc;
-use dnd_campaign_generator::services::{DatabaseServiceEnhanced, GenerationServiceEnhanced, GraphQLClient, HasuraSchemaGenerator, AnthropicClient, CampaignService};
+use dnd_campaign_generator::services::{DatabaseServiceEnhanced, GenerationServiceEnhanced, GraphQLClient, HasuraSchemaGenerator, AnthropicClient, CampaignService, generation_event_channel};
 use dnd_campaign_generator::models::campaign::CreateCampaignRequest;
 use serde_json::json;
 use tokio::sync::RwLock;
@@ -41,7 +41,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         db.clone(), 
         graphql_client.clone(),
         anthropic_client,
-        schema_generator_arc.clone()
+        schema_generator_arc.clone(),
+        1,
+        generation_event_channel(),
     );
     
     println!("\n2. Creating test campaign...");
@@ -1,7 +1,26 @@
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::{
+    migrate::{Migrate, MigrateError, Migrator},
+    postgres::PgPoolOptions,
+    PgPool,
+};
+use std::collections::HashMap;
 use std::time::Duration;
 
-pub async fn create_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
+/// The migrations in `backend/migrations`, embedded at compile time.
+///
+/// Applied migrations are recorded with their checksum in `_sqlx_migrations`; editing one that
+/// has already been applied is reported as an error, so schema changes always go in a new file.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Connect and bring the schema up to date.
+pub async fn create_pool(database_url: &str) -> Result<PgPool, MigrateError> {
+    let pool = connect(database_url).await?;
+    run_migrations(&pool).await?;
+    Ok(pool)
+}
+
+/// Connect without touching the schema.
+pub async fn connect(database_url: &str) -> Result<PgPool, sqlx::Error> {
     PgPoolOptions::new()
         .max_connections(10)
         .min_connections(1)
@@ -9,4 +28,80 @@ pub async fn create_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
         .idle_timeout(Duration::from_secs(600))
         .connect(database_url)
         .await
-}
\ No newline at end of file
+}
+
+/// Apply every pending migration in version order.
+pub async fn run_migrations(pool: &PgPool) -> Result<(), MigrateError> {
+    MIGRATOR.run(pool).await?;
+    tracing::info!("Database schema is at migration {}", MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0));
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationState {
+    Applied,
+    Pending,
+    /// Applied, but the file has changed since.
+    ChecksumMismatch,
+    /// Recorded in the database but not present in this build.
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+}
+
+/// Compare the embedded migrations with those recorded in the database, without applying any.
+pub async fn migration_status(pool: &PgPool) -> Result<Vec<MigrationStatus>, MigrateError> {
+    let mut conn = pool.acquire().await?;
+
+    // Listing applied migrations needs the bookkeeping table, which a fresh database lacks
+    let has_table: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(&mut *conn)
+        .await?;
+    let mut applied: HashMap<i64, Vec<u8>> = if has_table {
+        conn.list_applied_migrations()
+            .await?
+            .into_iter()
+            .map(|m| (m.version, m.checksum.into_owned()))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    let mut statuses: Vec<MigrationStatus> = MIGRATOR
+        .iter()
+        .filter(|m| m.migration_type.is_up_migration())
+        .map(|m| {
+            let state = match applied.remove(&m.version) {
+                Some(checksum) if checksum == *m.checksum => MigrationState::Applied,
+                Some(_) => MigrationState::ChecksumMismatch,
+                None => MigrationState::Pending,
+            };
+            MigrationStatus { version: m.version, description: m.description.to_string(), state }
+        })
+        .collect();
+
+    statuses.extend(applied.into_keys().map(|version| MigrationStatus {
+        version,
+        description: String::new(),
+        state: MigrationState::Unknown,
+    }));
+    statuses.sort_by_key(|s| s.version);
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrations_are_numbered_consecutively() {
+        let versions: Vec<i64> = MIGRATOR.iter().map(|m| m.version).collect();
+        let expected: Vec<i64> = (1..=versions.len() as i64).collect();
+        assert_eq!(versions, expected, "migrations must be numbered 0001, 0002, ... with no gaps or duplicates");
+    }
+}
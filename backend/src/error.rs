@@ -3,6 +3,7 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use crate::services::SchemaViolation;
 use serde_json::json;
 use thiserror::Error;
 
@@ -16,6 +17,19 @@ pub enum ApiError {
     
     #[error("Bad request: {0}")]
     BadRequest(String),
+
+    /// Hasura refused a query; `rejects_data` when it was the values sent that it refused.
+    #[error("GraphQL errors: {message}")]
+    GraphQL { message: String, rejects_data: bool },
+    
+    #[error("Generation cancelled")]
+    Cancelled,
+
+    #[error("Token budget exceeded: {phase} may use up to {requested} tokens but only {remaining} remain")]
+    TokenBudgetExceeded { phase: String, requested: u64, remaining: u64 },
+
+    #[error("{tool} output failed schema validation with {} violations", violations.len())]
+    InvalidToolOutput { tool: String, violations: Vec<SchemaViolation> },
     
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
@@ -26,6 +40,11 @@ pub enum ApiError {
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
+        let message;
+        let violations = match &self {
+            ApiError::InvalidToolOutput { violations, .. } => Some(json!(violations)),
+            _ => None,
+        };
         let (status, error_message) = match self {
             ApiError::Database(ref e) => {
                 tracing::error!("Database error: {:?}", e);
@@ -33,6 +52,19 @@ impl IntoResponse for ApiError {
             }
             ApiError::NotFound => (StatusCode::NOT_FOUND, "Resource not found"),
             ApiError::BadRequest(ref msg) => (StatusCode::BAD_REQUEST, msg.as_str()),
+            ApiError::GraphQL { .. } => {
+                message = self.to_string();
+                (StatusCode::BAD_REQUEST, message.as_str())
+            }
+            ApiError::Cancelled => (StatusCode::CONFLICT, "Generation was cancelled"),
+            ApiError::TokenBudgetExceeded { .. } => {
+                message = self.to_string();
+                (StatusCode::UNPROCESSABLE_ENTITY, message.as_str())
+            }
+            ApiError::InvalidToolOutput { .. } => {
+                message = self.to_string();
+                (StatusCode::BAD_GATEWAY, message.as_str())
+            }
             ApiError::Serialization(ref e) => {
                 tracing::error!("Serialization error: {:?}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Data processing error")
@@ -43,9 +75,13 @@ impl IntoResponse for ApiError {
             }
         };
 
-        let body = Json(json!({
+        let mut body = json!({
             "error": error_message,
-        }));
+        });
+        if let Some(violations) = violations {
+            body["violations"] = violations;
+        }
+        let body = Json(body);
 
         (status, body).into_response()
     }
@@ -1,10 +1,17 @@
 use crate::error::ApiResult;
-use crate::models::{Campaign, CampaignDetail, CreateCampaignRequest, UpdateCampaignRequest};
+use crate::models::{
+    Campaign, CampaignDetail, CampaignUsage, CreateCampaignRequest, GenerationCall, GenerationJobKind, GenerationLogParams,
+    RegeneratePhaseParams, ReplayCampaignRequest, UpdateCampaignRequest,
+};
 use crate::state::AppState;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    response::sse::{Event, KeepAlive, Sse},
     Json,
 };
+use futures::stream::Stream;
+use std::convert::Infallible;
+use tokio::sync::broadcast::error::RecvError;
 
 pub async fn create_campaign(
     State(state): State<AppState>,
@@ -58,21 +65,148 @@ pub async fn generate_campaign_content(
     State(state): State<AppState>,
     Path(id): Path<i32>,
 ) -> ApiResult<Json<serde_json::Value>> {
-    // Spawn generation task in background
-    let generation_service = state.generation_service.clone();
-    tokio::spawn(async move {
-        if let Err(e) = generation_service.generate_campaign_content(id).await {
-            tracing::error!("Failed to generate content for campaign {}: {}", id, e);
-        }
-    });
+    // Queue generation for a background worker
+    let job = state.job_queue.enqueue(id, GenerationJobKind::Generate).await?;
     
     Ok(Json(serde_json::json!({
         "campaign_id": id,
+        "job_id": job.id,
         "status": "generating",
         "message": "Content generation started. Check campaign status for updates."
     })))
 }
 
+pub async fn resume_campaign_generation(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> ApiResult<Json<serde_json::Value>> {
+    // Validate up front so the caller gets an error instead of a silent background failure
+    let plan = state.generation_service.plan_resume(id).await?;
+
+    let job = state.job_queue.enqueue(id, GenerationJobKind::Resume).await?;
+    
+    Ok(Json(serde_json::json!({
+        "campaign_id": id,
+        "job_id": job.id,
+        "status": "generating",
+        "completed_phases": plan.completed_phases,
+        "remaining_phases": plan.remaining_phases,
+        "message": "Generation resumed from the first incomplete phase. Check campaign status for updates."
+    })))
+}
+
+pub async fn regenerate_campaign_phase(
+    State(state): State<AppState>,
+    Path((id, phase)): Path<(i32, String)>,
+    Query(params): Query<RegeneratePhaseParams>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let plan = state.generation_service.plan_regeneration(id, &phase, params.cascade).await?;
+
+    let job = state
+        .job_queue
+        .enqueue(id, GenerationJobKind::Regenerate { phase, cascade: params.cascade })
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "campaign_id": id,
+        "job_id": job.id,
+        "status": "generating",
+        "phase": plan.phase,
+        "cascade": plan.cascade,
+        "rerun_phases": plan.rerun_phases,
+        "stale_phases": plan.stale_phases,
+        "message": "Phase regeneration started. Check campaign status for updates."
+    })))
+}
+
+/// Copy a campaign's settings into a new campaign and fill it from the phase outputs recorded
+/// for this one, re-running only the save path. No tokens are spent.
+pub async fn replay_campaign(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    body: Option<Json<ReplayCampaignRequest>>,
+) -> ApiResult<Json<serde_json::Value>> {
+    // Check the recording is complete before creating a campaign that could never be filled
+    state.generation_service.plan_replay(id).await?;
+
+    let Json(req) = body.unwrap_or_default();
+    let source = state.campaign_service.get_campaign(id).await?;
+    let name = req.name.unwrap_or_else(|| format!("{} (replay)", source.name));
+    let campaign = state.campaign_service.create_campaign(source.copy_request(name)).await?;
+
+    let job = state
+        .job_queue
+        .enqueue(campaign.id, GenerationJobKind::Replay { source_campaign_id: id })
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "campaign_id": campaign.id,
+        "source_campaign_id": id,
+        "job_id": job.id,
+        "status": "generating",
+        "message": "Replay started from recorded phase outputs. Check the new campaign's status for updates."
+    })))
+}
+
+pub async fn cancel_campaign_generation(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let job = state.job_queue.request_cancel(id).await?;
+
+    Ok(Json(serde_json::json!({
+        "campaign_id": id,
+        "job_id": job.id,
+        "status": "cancelling",
+        "message": "Cancellation requested. Partial output from the current phase will be rolled back."
+    })))
+}
+
+/// Token usage per phase with an estimated cost from the configured price table.
+pub async fn get_campaign_usage(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> ApiResult<Json<CampaignUsage>> {
+    let phases = state.database_service_enhanced.get_generation_usage(id).await?;
+    Ok(Json(CampaignUsage::from_phases(id, phases, &state.price_table)))
+}
+
+/// Every LLM call made while generating the campaign, with its prompt, parameters and response.
+pub async fn get_generation_log(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+    Query(params): Query<GenerationLogParams>,
+) -> ApiResult<Json<Vec<GenerationCall>>> {
+    let calls = state.database_service_enhanced.get_generation_calls(id, params.phase.as_deref()).await?;
+    Ok(Json(calls))
+}
+
+/// Stream generation progress for one campaign as Server-Sent Events.
+pub async fn generation_events(
+    State(state): State<AppState>,
+    Path(id): Path<i32>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let receiver = state.generation_events.subscribe();
+
+    let stream = futures::stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) if event.campaign_id() == id => match Event::default().event(event.event_name()).json_data(&event) {
+                    Ok(sse_event) => return Some((Ok(sse_event), receiver)),
+                    Err(e) => tracing::warn!("Failed to encode generation event: {}", e),
+                },
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Generation event stream for campaign {} skipped {} events", id, skipped);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
 pub async fn generate_encounters(
     State(_state): State<AppState>,
     Path(id): Path<i32>,
@@ -4,11 +4,11 @@ use axum::{
 };
 use dnd_campaign_generator::{
     config::Config,
-    db::create_pool,
+    db::{connect, create_pool, migration_status, run_migrations, MigrationState},
     handlers::{
-        create_campaign, delete_campaign, get_campaign_detail, 
-        generate_campaign_content, generate_encounters, health_check,
-        list_campaigns, update_campaign,
+        cancel_campaign_generation, create_campaign, delete_campaign, get_campaign_detail, 
+        generate_campaign_content, generate_encounters, generation_events, get_campaign_usage, get_generation_log, health_check,
+        list_campaigns, regenerate_campaign_phase, replay_campaign, resume_campaign_generation, update_campaign,
     },
     state::AppState,
 };
@@ -35,13 +35,44 @@ async fn main() -> anyhow::Result<()> {
     let config = Config::from_env()?;
     tracing::info!("Configuration loaded");
 
-    // Create database pool
+    // `migrate` applies pending migrations and `migrations status` lists them; both exit afterwards
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        [] => {}
+        ["migrate"] => {
+            let pool = connect(&config.database_url).await?;
+            run_migrations(&pool).await?;
+            return Ok(());
+        }
+        ["migrations", "status"] => {
+            let pool = connect(&config.database_url).await?;
+            for migration in migration_status(&pool).await? {
+                let state = match migration.state {
+                    MigrationState::Applied => "applied",
+                    MigrationState::Pending => "pending",
+                    MigrationState::ChecksumMismatch => "applied, file changed since",
+                    MigrationState::Unknown => "applied, missing from this build",
+                };
+                println!("{:04} {:<40} {}", migration.version, migration.description, state);
+            }
+            return Ok(());
+        }
+        _ => anyhow::bail!("Unknown command {:?}; expected `migrate` or `migrations status`", args.join(" ")),
+    }
+
+    // Create database pool, applying any pending migrations
     let pool = create_pool(&config.database_url).await?;
     tracing::info!("Database connection established");
 
     // Create app state
     let state = AppState::new(pool, &config).await?;
 
+    // Start generation workers; they also pick up jobs orphaned by a previous run
+    for _ in 0..config.generation_workers.max(1) {
+        tokio::spawn(state.job_queue.clone().run_worker());
+    }
+    tracing::info!("Started {} generation workers", config.generation_workers.max(1));
+
     // Build our application with routes
     let app = Router::new()
         .route("/health", get(health_check))
@@ -53,6 +84,13 @@ async fn main() -> anyhow::Result<()> {
                 .delete(delete_campaign),
         )
         .route("/api/campaigns/:id/generate", post(generate_campaign_content))
+        .route("/api/campaigns/:id/generate/resume", post(resume_campaign_generation))
+        .route("/api/campaigns/:id/generate/events", get(generation_events))
+        .route("/api/campaigns/:id/generate/cancel", post(cancel_campaign_generation))
+        .route("/api/campaigns/:id/phases/:phase/regenerate", post(regenerate_campaign_phase))
+        .route("/api/campaigns/:id/replay", post(replay_campaign))
+        .route("/api/campaigns/:id/usage", get(get_campaign_usage))
+        .route("/api/campaigns/:id/generation-log", get(get_generation_log))
         .route("/api/campaigns/:id/encounters", post(generate_encounters))
         .layer(
             TraceLayer::new_for_http()
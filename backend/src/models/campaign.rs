@@ -158,6 +158,85 @@ impl PhaseInfo {
 
         Ok(())
     }
+
+    /// Phases that still need to run, in execution order, given the phases already completed.
+    pub fn remaining_phases(completed_phases: &[String]) -> Vec<PhaseInfo> {
+        Self::get_all_phases()
+            .into_iter()
+            .filter(|p| !completed_phases.contains(&p.name))
+            .collect()
+    }
+
+    /// All phases up to and including `phase_name`. Used for campaigns generated before
+    /// per-phase tracking existed, where only the last completed phase name was recorded.
+    pub fn completed_through(phase_name: &str) -> Vec<String> {
+        let Some(last) = Self::get_phase_info(phase_name) else {
+            return Vec::new();
+        };
+
+        Self::get_all_phases()
+            .into_iter()
+            .filter(|p| p.number <= last.number)
+            .map(|p| p.name)
+            .collect()
+    }
+
+    /// Every phase that depends on `phase_name`, directly or transitively, in execution order.
+    pub fn dependents(phase_name: &str) -> Vec<PhaseInfo> {
+        let mut affected = vec![phase_name.to_string()];
+        let mut dependents = Vec::new();
+
+        // Phases are listed in execution order, so a single pass picks up transitive dependents
+        for phase in Self::get_all_phases() {
+            if phase.dependencies.iter().any(|d| affected.contains(d)) {
+                affected.push(phase.name.clone());
+                dependents.push(phase);
+            }
+        }
+
+        dependents
+    }
+
+    /// Every phase `phase_name` depends on, directly or through other phases, in execution order.
+    pub fn ancestors(phase_name: &str) -> Vec<PhaseInfo> {
+        let mut needed = Self::get_phase_info(phase_name).map(|p| p.dependencies).unwrap_or_default();
+        let mut ancestors = Vec::new();
+
+        // Walking backwards, a phase is always reached after every phase that depends on it
+        for phase in Self::get_all_phases().into_iter().rev() {
+            if needed.contains(&phase.name) {
+                needed.extend(phase.dependencies.iter().cloned());
+                ancestors.push(phase);
+            }
+        }
+
+        ancestors.reverse();
+        ancestors
+    }
+
+    /// Order `phases` so each one comes after any of its dependencies that are also in the set.
+    /// Dependencies outside the set are assumed to have completed already.
+    pub fn topological_order(phases: Vec<PhaseInfo>) -> Result<Vec<PhaseInfo>, String> {
+        let scheduled: Vec<String> = phases.iter().map(|p| p.name.clone()).collect();
+        let mut pending = phases;
+        let mut ordered: Vec<PhaseInfo> = Vec::with_capacity(pending.len());
+
+        while !pending.is_empty() {
+            let ready = pending.iter().position(|phase| {
+                phase.dependencies.iter().all(|d| !scheduled.contains(d) || ordered.iter().any(|o| &o.name == d))
+            });
+
+            match ready {
+                Some(index) => ordered.push(pending.remove(index)),
+                None => {
+                    let names: Vec<&str> = pending.iter().map(|p| p.name.as_str()).collect();
+                    return Err(format!("Dependency cycle between phases: {}", names.join(", ")));
+                }
+            }
+        }
+
+        Ok(ordered)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -184,6 +263,37 @@ pub struct Campaign {
     pub updated_at: DateTime<Utc>,
 }
 
+impl Campaign {
+    /// A request creating a new campaign with this campaign's settings and player characters.
+    pub fn copy_request(&self, name: String) -> CreateCampaignRequest {
+        CreateCampaignRequest {
+            name,
+            setting: self.setting.clone(),
+            themes: self.themes.clone(),
+            player_characters: Some(self.player_characters.clone()),
+            progression_type: Some(self.progression_type.clone()),
+            tone: Some(self.tone.clone()),
+            difficulty: Some(self.difficulty.clone()),
+            starting_level: Some(self.starting_level),
+            campaign_length: Some(self.campaign_length.clone()),
+            additional_notes: self.additional_notes.clone(),
+            metadata: Some(self.metadata.clone()),
+            use_standard_content: self.metadata.get("use_standard_content").and_then(JsonValue::as_bool),
+            world_building: None,
+            campaign_specifics: None,
+            generation_preferences: None,
+        }
+    }
+
+    /// The token budget from the campaign's generation preferences, if one was set.
+    pub fn max_token_budget(&self) -> Option<u64> {
+        self.metadata
+            .get("generation_preferences")
+            .and_then(|prefs| prefs.get("max_token_budget"))
+            .and_then(JsonValue::as_u64)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateCampaignRequest {
     pub name: String,
@@ -315,6 +425,41 @@ pub struct GenerationPreferences {
     pub encounter_variety: String,
     pub magic_item_frequency: String,
     pub faction_involvement: String,
+    /// Maximum input plus output tokens generation may spend on this campaign
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_token_budget: Option<u64>,
+}
+
+/// What to do with downstream phases when a single phase is regenerated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CascadeMode {
+    /// Clear and re-run every dependent phase after the target phase.
+    #[default]
+    Rerun,
+    /// Leave dependent phases in place and flag them as stale.
+    MarkStale,
+}
+
+impl CascadeMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CascadeMode::Rerun => "rerun",
+            CascadeMode::MarkStale => "mark_stale",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegeneratePhaseParams {
+    #[serde(default)]
+    pub cascade: CascadeMode,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplayCampaignRequest {
+    /// Name of the new campaign; defaults to the source campaign's name with " (replay)"
+    pub name: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -325,4 +470,84 @@ pub struct UpdateCampaignRequest {
     pub player_characters: Option<JsonValue>,
     pub status: Option<String>,
     pub metadata: Option<JsonValue>,
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remaining_phases_skips_completed() {
+        let completed = vec![PHASE_1A_CORE_WORLD.to_string(), PHASE_1B_CHARACTER_BUILDING.to_string()];
+        let remaining = PhaseInfo::remaining_phases(&completed);
+
+        assert_eq!(remaining.len(), 7);
+        assert_eq!(remaining[0].name, PHASE_1C_SOCIAL_FRAMEWORK);
+        assert!(remaining.iter().all(|p| !completed.contains(&p.name)));
+    }
+
+    #[test]
+    fn test_completed_through_legacy_phase() {
+        let completed = PhaseInfo::completed_through(PHASE_2A_PC_ENTITIES);
+        assert_eq!(completed.len(), 4);
+        assert_eq!(completed.last().map(String::as_str), Some(PHASE_2A_PC_ENTITIES));
+
+        assert!(PhaseInfo::completed_through("world_building").is_empty());
+    }
+
+    #[test]
+    fn test_dependents_are_transitive() {
+        let names: Vec<String> = PhaseInfo::dependents(PHASE_2B_PC_LOCATIONS)
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+
+        // 3C only depends on 2B through 3A and 3B
+        assert_eq!(names, vec![
+            PHASE_2C_PC_ITEMS,
+            PHASE_3A_QUESTS_ENCOUNTERS,
+            PHASE_3B_WORLD_POPULATION,
+            PHASE_3C_RELATIONSHIPS,
+        ]);
+        assert!(PhaseInfo::dependents(PHASE_3C_RELATIONSHIPS).is_empty());
+    }
+
+    #[test]
+    fn test_ancestors_are_transitive() {
+        let names: Vec<String> = PhaseInfo::ancestors(PHASE_2B_PC_LOCATIONS)
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+
+        // 2B only lists 2A, which brings in all of phase 1
+        assert_eq!(names, vec![
+            PHASE_1A_CORE_WORLD,
+            PHASE_1B_CHARACTER_BUILDING,
+            PHASE_1C_SOCIAL_FRAMEWORK,
+            PHASE_2A_PC_ENTITIES,
+        ]);
+        assert_eq!(PhaseInfo::ancestors(PHASE_3C_RELATIONSHIPS).len(), 8);
+        assert!(PhaseInfo::ancestors(PHASE_1A_CORE_WORLD).is_empty());
+    }
+
+    #[test]
+    fn test_topological_order_respects_dependencies() {
+        let mut phases = PhaseInfo::get_all_phases();
+        phases.reverse();
+
+        let ordered = PhaseInfo::topological_order(phases).unwrap();
+        assert_eq!(ordered.len(), 9);
+        for (index, phase) in ordered.iter().enumerate() {
+            for dependency in &phase.dependencies {
+                assert!(ordered[..index].iter().any(|p| &p.name == dependency));
+            }
+        }
+    }
+
+    #[test]
+    fn test_topological_order_detects_cycles() {
+        let mut phases = PhaseInfo::get_all_phases();
+        phases[0].dependencies.push(PHASE_3C_RELATIONSHIPS.to_string());
+
+        assert!(PhaseInfo::topological_order(phases).is_err());
+    }
+}
@@ -26,7 +26,7 @@ pub struct Subrace {
     pub campaign_id: i32,
     pub race_id: i32,
     pub name: String,
-    pub description: String,
+    pub description: Option<String>,
     pub additional_traits: JsonValue,
     pub additional_ability_scores: JsonValue,
     pub created_at: DateTime<Utc>,
@@ -56,7 +56,7 @@ pub struct Subclass {
     pub campaign_id: i32,
     pub class_id: i32,
     pub name: String,
-    pub description: String,
+    pub description: Option<String>,
     pub additional_features: JsonValue,
     pub additional_spells: JsonValue,
     pub created_at: DateTime<Utc>,
@@ -78,10 +78,10 @@ pub struct Creature {
     pub entity_id: i32,
     pub creature_type: String,
     pub size: String,
-    pub alignment: String,
-    pub armor_class: i32,
-    pub hit_points: i32,
-    pub hit_dice: String,
+    pub alignment: Option<String>,
+    pub armor_class: Option<i32>,
+    pub hit_points: Option<i32>,
+    pub hit_dice: Option<String>,
     pub speed: JsonValue,
     pub ability_scores: JsonValue,
     pub saving_throws: JsonValue,
@@ -92,7 +92,7 @@ pub struct Creature {
     pub senses: JsonValue,
     pub languages: Vec<String>,
     pub challenge_rating: f32,
-    pub proficiency_bonus: i32,
+    pub proficiency_bonus: Option<i32>,
     pub actions: JsonValue,
     pub legendary_actions: JsonValue,
     pub lair_actions: JsonValue,
@@ -110,11 +110,11 @@ pub struct Flora {
     pub campaign_id: i32,
     pub entity_id: i32,
     pub plant_type: String,
-    pub size: String,
+    pub size: Option<String>,
     pub rarity: String,
     pub habitat: JsonValue,
     pub growing_conditions: JsonValue,
-    pub appearance: String,
+    pub appearance: Option<String>,
     pub uses: JsonValue,
     pub magical_properties: JsonValue,
     pub harvest_difficulty: Option<String>,
@@ -128,15 +128,15 @@ pub struct Fauna {
     pub id: i32,
     pub campaign_id: i32,
     pub entity_id: i32,
-    pub animal_type: String,
-    pub size: String,
+    pub animal_type: Option<String>,
+    pub size: Option<String>,
     pub habitat: JsonValue,
-    pub diet: String,
+    pub diet: Option<String>,
     pub behavior: JsonValue,
     pub social_structure: Option<String>,
-    pub appearance: String,
+    pub appearance: Option<String>,
     pub abilities: JsonValue,
-    pub domestication_status: String,
+    pub domestication_status: Option<String>,
     pub rarity: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
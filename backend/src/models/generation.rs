@@ -1,4 +1,8 @@
+use super::CascadeMode;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sqlx::FromRow;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeneratedCampaignContent {
@@ -94,4 +98,175 @@ pub struct Enemy {
     pub hp: i32,
     pub ac: i32,
     pub special_abilities: Option<Vec<String>>,
-}
\ No newline at end of file
+}
+
+/// A queued unit of generation work, claimed by a worker under a time-limited lease.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GenerationJob {
+    pub id: i32,
+    pub campaign_id: i32,
+    pub kind: String,
+    pub phase_name: Option<String>,
+    pub cascade_mode: Option<String>,
+    pub state: String,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub worker_id: Option<String>,
+    pub lease_expires_at: Option<DateTime<Utc>>,
+    pub cancel_requested: bool,
+    /// Campaign whose recorded phase outputs a `replay` job saves
+    pub source_campaign_id: Option<i32>,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One LLM call made while generating a campaign, as stored in the `generation_calls` audit log.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GenerationCall {
+    pub id: i32,
+    pub campaign_id: i32,
+    pub phase_name: String,
+    pub provider: String,
+    pub model: String,
+    pub system_prompt: Option<String>,
+    /// The rendered conversation sent to the model
+    pub messages: JsonValue,
+    pub tool_name: String,
+    /// SHA-256 of the tool's input schema, so calls made against different schemas can be told apart
+    pub tool_schema_hash: String,
+    pub temperature: f32,
+    pub max_tokens: i32,
+    /// The provider's response, or `None` if the call failed
+    pub raw_response: Option<JsonValue>,
+    pub error_message: Option<String>,
+    pub input_tokens: i32,
+    pub output_tokens: i32,
+    pub duration_ms: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A call to add to the audit log; the database assigns the id and timestamp.
+#[derive(Debug, Clone)]
+pub struct NewGenerationCall {
+    pub campaign_id: i32,
+    pub phase_name: String,
+    pub provider: String,
+    pub model: String,
+    pub system_prompt: Option<String>,
+    pub messages: JsonValue,
+    pub tool_name: String,
+    pub tool_schema_hash: String,
+    pub temperature: f32,
+    pub max_tokens: u32,
+    pub raw_response: Option<JsonValue>,
+    pub error_message: Option<String>,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub duration_ms: u64,
+}
+
+/// The tool input a phase was saved from, kept so the generation can be replayed.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PhaseOutput {
+    pub phase_name: String,
+    pub tool_name: String,
+    pub tool_input: JsonValue,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenerationLogParams {
+    /// Only return calls made for this phase
+    pub phase: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenerationJobKind {
+    Generate,
+    Resume,
+    Regenerate { phase: String, cascade: CascadeMode },
+    Replay { source_campaign_id: i32 },
+}
+
+impl GenerationJobKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GenerationJobKind::Generate => "generate",
+            GenerationJobKind::Resume => "resume",
+            GenerationJobKind::Regenerate { .. } => "regenerate",
+            GenerationJobKind::Replay { .. } => "replay",
+        }
+    }
+}
+
+impl GenerationJob {
+    pub fn job_kind(&self) -> Result<GenerationJobKind, String> {
+        match self.kind.as_str() {
+            "generate" => Ok(GenerationJobKind::Generate),
+            "resume" => Ok(GenerationJobKind::Resume),
+            "regenerate" => {
+                let phase = self
+                    .phase_name
+                    .clone()
+                    .ok_or_else(|| format!("Regenerate job {} has no phase", self.id))?;
+                let cascade = match self.cascade_mode.as_deref() {
+                    Some("mark_stale") => CascadeMode::MarkStale,
+                    _ => CascadeMode::Rerun,
+                };
+                Ok(GenerationJobKind::Regenerate { phase, cascade })
+            }
+            "replay" => {
+                let source_campaign_id = self
+                    .source_campaign_id
+                    .ok_or_else(|| format!("Replay job {} has no source campaign", self.id))?;
+                Ok(GenerationJobKind::Replay { source_campaign_id })
+            }
+            other => Err(format!("Unknown generation job kind: {}", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(kind: &str, phase_name: Option<&str>, cascade_mode: Option<&str>) -> GenerationJob {
+        GenerationJob {
+            id: 1,
+            campaign_id: 1,
+            kind: kind.to_string(),
+            phase_name: phase_name.map(str::to_string),
+            cascade_mode: cascade_mode.map(str::to_string),
+            state: "pending".to_string(),
+            attempts: 0,
+            max_attempts: 3,
+            worker_id: None,
+            lease_expires_at: None,
+            cancel_requested: false,
+            source_campaign_id: None,
+            error_message: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_job_kind_from_row() {
+        assert_eq!(job("generate", None, None).job_kind(), Ok(GenerationJobKind::Generate));
+        assert_eq!(
+            job("regenerate", Some("phase_2a_pc_entities"), Some("mark_stale")).job_kind(),
+            Ok(GenerationJobKind::Regenerate {
+                phase: "phase_2a_pc_entities".to_string(),
+                cascade: CascadeMode::MarkStale,
+            })
+        );
+        assert!(job("regenerate", None, None).job_kind().is_err());
+        assert!(job("replay", None, None).job_kind().is_err());
+
+        let mut replay = job("replay", None, None);
+        replay.source_campaign_id = Some(7);
+        assert_eq!(replay.job_kind(), Ok(GenerationJobKind::Replay { source_campaign_id: 7 }));
+        assert!(job("explode", None, None).job_kind().is_err());
+    }
+}
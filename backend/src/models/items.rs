@@ -64,8 +64,8 @@ pub struct Weapon {
     pub campaign_id: i32,
     pub item_id: i32,
     pub weapon_type: String,
-    pub damage_dice: String,
-    pub damage_type: String,
+    pub damage_dice: Option<String>,
+    pub damage_type: Option<String>,
     pub weapon_properties: Vec<String>,
     pub range_normal: Option<i32>,
     pub range_long: Option<i32>,
@@ -87,12 +87,12 @@ pub struct Armor {
     pub campaign_id: i32,
     pub item_id: i32,
     pub armor_type: String,
-    pub armor_class: i32,
+    pub armor_class: Option<i32>,
     pub dex_modifier_cap: Option<i32>,
     pub strength_requirement: Option<i32>,
     pub stealth_disadvantage: bool,
-    pub don_time: String,
-    pub doff_time: String,
+    pub don_time: Option<String>,
+    pub doff_time: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -107,7 +107,7 @@ pub struct Potion {
     pub duration: Option<String>,
     pub onset_time: Option<String>,
     pub side_effects: JsonValue,
-    pub brewing_difficulty: String,
+    pub brewing_difficulty: Option<String>,
     pub ingredients: JsonValue,
     pub brewing_time: Option<String>,
     pub created_at: DateTime<Utc>,
@@ -121,12 +121,12 @@ pub struct SpellScroll {
     pub item_id: i32,
     pub spell_name: String,
     pub spell_level: i32,
-    pub school_of_magic: String,
-    pub casting_time: String,
-    pub range_area: String,
-    pub duration: String,
+    pub school_of_magic: Option<String>,
+    pub casting_time: Option<String>,
+    pub range_area: Option<String>,
+    pub duration: Option<String>,
     pub components: JsonValue,
-    pub spell_description: String,
+    pub spell_description: Option<String>,
     pub save_dc: Option<i32>,
     pub attack_bonus: Option<i32>,
     pub created_at: DateTime<Utc>,
@@ -141,7 +141,7 @@ pub struct SentientItem {
     pub intelligence: i32,
     pub wisdom: i32,
     pub charisma: i32,
-    pub alignment: String,
+    pub alignment: Option<String>,
     pub personality_traits: JsonValue,
     pub ideals: JsonValue,
     pub bonds: JsonValue,
@@ -149,8 +149,8 @@ pub struct SentientItem {
     pub languages: Vec<String>,
     pub senses: JsonValue,
     pub communication_method: String,
-    pub ego_score: i32,
-    pub purpose: String,
+    pub ego_score: Option<i32>,
+    pub purpose: Option<String>,
     pub special_purpose: Option<String>,
     pub conflict_resolution: JsonValue,
     pub backstory: Option<String>,
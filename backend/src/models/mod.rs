@@ -10,6 +10,7 @@ pub mod location_enhanced;
 pub mod npc;
 pub mod quest_hook;
 pub mod social_systems;
+pub mod usage;
 pub mod world_building;
 
 pub use campaign::*;
@@ -24,4 +25,5 @@ pub use location_enhanced::*;
 pub use npc::*;
 pub use quest_hook::*;
 pub use social_systems::*;
+pub use usage::*;
 pub use world_building::*;
\ No newline at end of file
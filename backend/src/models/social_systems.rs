@@ -92,8 +92,8 @@ pub struct FactionRelationship {
 pub struct EntityLocation {
     pub id: i32,
     pub campaign_id: i32,
-    pub entity_id: i32,
-    pub location_id: i32,
+    pub entity_id: Option<i32>,
+    pub location_id: Option<i32>,
     pub relationship_type: String,
     pub description: Option<String>,
     pub frequency: String,
@@ -108,8 +108,8 @@ pub struct EntityLocation {
 pub struct EntityFaction {
     pub id: i32,
     pub campaign_id: i32,
-    pub entity_id: i32,
-    pub faction_id: i32,
+    pub entity_id: Option<i32>,
+    pub faction_id: Option<i32>,
     pub rank: Option<String>,
     pub role: Option<String>,
     pub loyalty_level: String,
@@ -125,8 +125,8 @@ pub struct EntityFaction {
 pub struct EntityItem {
     pub id: i32,
     pub campaign_id: i32,
-    pub entity_id: i32,
-    pub item_id: i32,
+    pub entity_id: Option<i32>,
+    pub item_id: Option<i32>,
     pub relationship_type: String,
     pub quantity: i32,
     pub condition_state: String,
@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+
+/// USD price per million tokens for one model.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+/// Prices keyed by model name. Loaded from the JSON file named by `LLM_PRICE_TABLE`, falling
+/// back to list prices for the models this project ships with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceTable(pub HashMap<String, ModelPrice>);
+
+impl Default for PriceTable {
+    fn default() -> Self {
+        let mut prices = HashMap::new();
+        prices.insert(
+            "claude-sonnet-4-20250514".to_string(),
+            ModelPrice { input_per_million: 3.0, output_per_million: 15.0 },
+        );
+        prices.insert("mock".to_string(), ModelPrice { input_per_million: 0.0, output_per_million: 0.0 });
+        PriceTable(prices)
+    }
+}
+
+impl PriceTable {
+    pub fn from_file(path: &str) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(PriceTable(serde_json::from_str(&contents)?))
+    }
+
+    /// Estimated cost in USD, or `None` for models missing from the table.
+    pub fn cost(&self, model: &str, input_tokens: i64, output_tokens: i64) -> Option<f64> {
+        self.0.get(model).map(|price| {
+            (input_tokens as f64 * price.input_per_million + output_tokens as f64 * price.output_per_million)
+                / 1_000_000.0
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PhaseUsage {
+    pub phase_name: String,
+    pub model: String,
+    pub calls: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    #[sqlx(skip)]
+    pub estimated_cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignUsage {
+    pub campaign_id: i32,
+    pub phases: Vec<PhaseUsage>,
+    pub total_input_tokens: i64,
+    pub total_output_tokens: i64,
+    /// Sum of the phases that have a known price
+    pub estimated_cost_usd: f64,
+    /// Models that were used but have no entry in the price table
+    pub unpriced_models: Vec<String>,
+}
+
+impl CampaignUsage {
+    pub fn from_phases(campaign_id: i32, mut phases: Vec<PhaseUsage>, prices: &PriceTable) -> Self {
+        let mut unpriced_models = Vec::new();
+        for phase in &mut phases {
+            phase.estimated_cost_usd = prices.cost(&phase.model, phase.input_tokens, phase.output_tokens);
+            if phase.estimated_cost_usd.is_none() && !unpriced_models.contains(&phase.model) {
+                unpriced_models.push(phase.model.clone());
+            }
+        }
+
+        CampaignUsage {
+            campaign_id,
+            total_input_tokens: phases.iter().map(|p| p.input_tokens).sum(),
+            total_output_tokens: phases.iter().map(|p| p.output_tokens).sum(),
+            estimated_cost_usd: phases.iter().filter_map(|p| p.estimated_cost_usd).sum(),
+            unpriced_models,
+            phases,
+        }
+    }
+}
+
+/// A campaign's token allowance during a generation run. Tokens already spent (including by
+/// earlier runs) count against the limit, and each model call reserves an estimate of its input
+/// plus `max_tokens` until it returns, so that calls made in parallel cannot overspend together.
+#[derive(Debug, Clone, Copy)]
+pub struct TokenBudget {
+    pub limit: u64,
+    pub spent: u64,
+    pub reserved: u64,
+}
+
+impl TokenBudget {
+    pub fn new(limit: u64, spent: u64) -> Self {
+        Self { limit, spent, reserved: 0 }
+    }
+
+    pub fn remaining(&self) -> u64 {
+        self.limit.saturating_sub(self.spent + self.reserved)
+    }
+
+    /// Reserve `amount` tokens for a call, or return the remaining budget if it does not fit.
+    pub fn reserve(&mut self, amount: u64) -> Result<(), u64> {
+        let remaining = self.remaining();
+        if amount > remaining {
+            return Err(remaining);
+        }
+        self.reserved += amount;
+        Ok(())
+    }
+
+    /// Swap a call's reservation for the tokens it actually used.
+    pub fn settle(&mut self, reserved: u64, used: u64) {
+        self.reserved = self.reserved.saturating_sub(reserved);
+        self.spent += used;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phase(name: &str, model: &str, input_tokens: i64, output_tokens: i64) -> PhaseUsage {
+        PhaseUsage {
+            phase_name: name.to_string(),
+            model: model.to_string(),
+            calls: 1,
+            input_tokens,
+            output_tokens,
+            estimated_cost_usd: None,
+        }
+    }
+
+    #[test]
+    fn test_campaign_usage_totals_and_cost() {
+        let usage = CampaignUsage::from_phases(
+            1,
+            vec![
+                phase("phase_1a_core_world", "claude-sonnet-4-20250514", 1_000_000, 100_000),
+                phase("phase_1b_character_building", "local-llama", 5_000, 2_000),
+            ],
+            &PriceTable::default(),
+        );
+
+        assert_eq!(usage.total_input_tokens, 1_005_000);
+        assert_eq!(usage.total_output_tokens, 102_000);
+        assert!((usage.estimated_cost_usd - 4.5).abs() < 1e-9);
+        assert_eq!(usage.unpriced_models, vec!["local-llama".to_string()]);
+    }
+
+    #[test]
+    fn test_token_budget_reservations() {
+        let mut budget = TokenBudget::new(20_000, 5_000);
+        assert_eq!(budget.remaining(), 15_000);
+
+        assert!(budget.reserve(8_000).is_ok());
+        assert_eq!(budget.reserve(8_000), Err(7_000));
+
+        // The finished call used less than it reserved
+        budget.settle(8_000, 3_000);
+        assert_eq!(budget.remaining(), 12_000);
+        assert_eq!(budget.reserved, 0);
+        assert!(budget.reserve(8_000).is_ok());
+    }
+}
@@ -1,38 +1,75 @@
 use crate::error::{ApiError, ApiResult};
 use crate::models::{Campaign, GeneratedCampaignContent};
-use reqwest::{Client, StatusCode};
+use crate::services::{
+    notify_retry, notify_stream_progress, parse_retry_after, report_usage, LlmProvider, RetryClock, RetryNotice, RetryPolicy,
+    SseParser, StreamAccumulator, StreamEvent, StreamProgress,
+};
+use async_trait::async_trait;
+use reqwest::{header::RETRY_AFTER, Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::error::Error;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
 const MODEL: &str = "claude-sonnet-4-20250514";
 const MAX_TOKENS: u32 = 20000;
+/// A streamed response that sends nothing for this long is treated as stalled and retried.
+const STREAM_STALL_TIMEOUT: Duration = Duration::from_secs(60);
 
 #[derive(Debug, Clone)]
 pub struct AnthropicClient {
     client: Client,
     api_key: String,
+    retry_policy: RetryPolicy,
+    streaming: bool,
+    api_url: String,
+    stall_timeout: Duration,
 }
 
 #[derive(Debug, Serialize)]
 pub struct AnthropicRequest {
     pub model: String,
     pub max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
     pub messages: Vec<Message>,
     pub temperature: f32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<Vec<Tool>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_choice: Option<ToolChoice>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub role: String,
-    pub content: String,
+    pub content: MessageContent,
+}
+
+/// A message body: plain text, or content blocks for turns that carry tool calls and results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Blocks(Vec<Content>),
+}
+
+impl Message {
+    pub fn user(text: impl Into<String>) -> Self {
+        Self { role: "user".to_string(), content: MessageContent::Text(text.into()) }
+    }
+
+    pub fn user_blocks(blocks: Vec<Content>) -> Self {
+        Self { role: "user".to_string(), content: MessageContent::Blocks(blocks) }
+    }
+
+    pub fn assistant_blocks(blocks: Vec<Content>) -> Self {
+        Self { role: "assistant".to_string(), content: MessageContent::Blocks(blocks) }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -50,13 +87,13 @@ pub struct ToolChoice {
     pub name: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct AnthropicResponse {
     pub content: Vec<Content>,
     pub usage: Option<Usage>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum Content {
     #[serde(rename = "text")]
@@ -67,14 +104,38 @@ pub enum Content {
         name: String,
         input: Value,
     },
+    #[serde(rename = "tool_result")]
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        is_error: bool,
+    },
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct Usage {
     pub input_tokens: u32,
     pub output_tokens: u32,
 }
 
+/// Tool input returned by the model, along with the tokens the call consumed. `id` identifies
+/// the tool call so a follow-up turn can answer it with a tool result.
+#[derive(Debug, Clone)]
+pub struct ToolCallResult {
+    pub id: String,
+    pub input: Value,
+    pub usage: Usage,
+    /// The provider's whole response, kept for the generation audit log
+    pub raw_response: Value,
+}
+
+/// Why reading a streamed response failed; stalls and dropped streams are worth retrying.
+enum StreamFailure {
+    Retryable(String),
+    Failed(ApiError),
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AnthropicError {
     pub error: ErrorDetail,
@@ -93,7 +154,26 @@ impl AnthropicClient {
             .build()
             .expect("Failed to build HTTP client");
 
-        Self { client, api_key }
+        Self {
+            client,
+            api_key,
+            retry_policy: RetryPolicy::default(),
+            streaming: false,
+            api_url: ANTHROPIC_API_URL.to_string(),
+            stall_timeout: STREAM_STALL_TIMEOUT,
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Stream responses as Server-Sent Events, reporting progress to `STREAM_LISTENER` and
+    /// retrying calls that stall.
+    pub fn with_streaming(mut self, streaming: bool) -> Self {
+        self.streaming = streaming;
+        self
     }
 
     pub async fn generate_campaign_content(&self, campaign: &Campaign) -> ApiResult<GeneratedCampaignContent> {
@@ -103,7 +183,7 @@ impl AnthropicClient {
         let response = self.generate_with_tool(&prompt, tool, MAX_TOKENS, 0.8).await?;
         
         // Parse JSON into our structure
-        let generated_content: GeneratedCampaignContent = serde_json::from_value(response)
+        let generated_content: GeneratedCampaignContent = serde_json::from_value(response.input)
             .map_err(|e| {
                 error!("Failed to parse AI response: {}", e);
                 ApiError::BadRequest(format!("Invalid AI response format: {}", e))
@@ -124,15 +204,12 @@ impl AnthropicClient {
         let request = AnthropicRequest {
             model: MODEL.to_string(),
             max_tokens,
-            messages: vec![
-                Message {
-                    role: "user".to_string(),
-                    content: prompt.to_string(),
-                }
-            ],
+            system: None,
+            messages: vec![Message::user(prompt)],
             temperature,
             tools: None,
             tool_choice: None,
+            stream: self.streaming,
         };
 
         let response = self.send_request(request).await?;
@@ -143,37 +220,48 @@ impl AnthropicClient {
         
         match content {
             Content::Text { text } => Ok(text.clone()),
-            Content::ToolUse { .. } => Err(ApiError::BadRequest("Unexpected tool use response".to_string())),
+            _ => Err(ApiError::BadRequest("Unexpected tool use response".to_string())),
         }
     }
     
-    pub async fn generate_with_tool(&self, prompt: &str, tool: Tool, max_tokens: u32, temperature: f32) -> ApiResult<Value> {
-        info!("Generating content with tool: {}", tool.name);
+    pub async fn generate_with_tool(&self, prompt: &str, tool: Tool, max_tokens: u32, temperature: f32) -> ApiResult<ToolCallResult> {
+        self.generate_with_tool_messages(None, vec![Message::user(prompt)], tool, max_tokens, temperature).await
+    }
+
+    /// Continue a conversation and force the model to answer with a call to `tool`.
+    pub async fn generate_with_tool_messages(
+        &self,
+        system: Option<&str>,
+        messages: Vec<Message>,
+        tool: Tool,
+        max_tokens: u32,
+        temperature: f32,
+    ) -> ApiResult<ToolCallResult> {
+        info!("Generating content with tool: {} ({} messages)", tool.name, messages.len());
         
         let request = AnthropicRequest {
             model: MODEL.to_string(),
             max_tokens,
-            messages: vec![
-                Message {
-                    role: "user".to_string(),
-                    content: prompt.to_string(),
-                }
-            ],
+            system: system.map(str::to_string),
+            messages,
             temperature,
             tools: Some(vec![tool.clone()]),
             tool_choice: Some(ToolChoice {
                 choice_type: "tool".to_string(),
                 name: Some(tool.name.clone()),
             }),
+            stream: self.streaming,
         };
 
         let response = self.send_request(request).await?;
+        let usage = response.usage.unwrap_or_default();
+        let raw_response = serde_json::to_value(&response)?;
         
         // Find the tool use response
-        for content in &response.content {
+        for content in response.content {
             match content {
-                Content::ToolUse { name, input, .. } if name == &tool.name => {
-                    return Ok(input.clone());
+                Content::ToolUse { id, name, input } if name == tool.name => {
+                    return Ok(ToolCallResult { id, input, usage, raw_response });
                 }
                 _ => continue,
             }
@@ -187,84 +275,196 @@ impl AnthropicClient {
         info!("API Key present: {}", !self.api_key.is_empty());
         info!("API Key first 10 chars: {}...", &self.api_key.chars().take(10).collect::<String>());
         debug!("Request model: {}", request.model);
-        
-        let response_result = self.client
-            .post(ANTHROPIC_API_URL)
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await;
-            
-        let response = match response_result {
-            Ok(resp) => resp,
-            Err(e) => {
-                error!("Network error calling Anthropic API: {}", e);
-                error!("Error details: {:?}", e);
-                error!("Error source: {:?}", e.source());
-                if e.is_request() {
-                    error!("Request construction error");
-                } else if e.is_connect() {
-                    error!("Connection error");
-                } else if e.is_body() {
-                    error!("Body error");
-                } else if e.is_decode() {
-                    error!("Decode error");
+
+        let started = Instant::now();
+        let retry_clock = RetryClock::current();
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            let attempt_started = Instant::now();
+
+            let response_result = self.client
+                .post(&self.api_url)
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", "2023-06-01")
+                .header("content-type", "application/json")
+                .json(&request)
+                .send()
+                .await;
+
+            let response = match response_result {
+                Ok(resp) => resp,
+                Err(e) => {
+                    error!("Network error calling Anthropic API: {}", e);
+                    error!("Error details: {:?}", e);
+                    error!("Error source: {:?}", e.source());
+                    if e.is_request() {
+                        error!("Request construction error");
+                    } else if e.is_connect() {
+                        error!("Connection error");
+                    } else if e.is_body() {
+                        error!("Body error");
+                    } else if e.is_decode() {
+                        error!("Decode error");
+                    }
+
+                    if RetryPolicy::is_retryable_error(&e) {
+                        let delay = self.retry_policy.delay_for(attempt, None);
+                        if self.retry_policy.should_retry(attempt, retry_clock.spent() + attempt_started.elapsed(), delay) {
+                            self.wait_before_retry(&retry_clock, attempt, attempt_started, delay, format!("network error: {}", e)).await;
+                            continue;
+                        }
+                    }
+                    return Err(ApiError::Internal(anyhow::anyhow!("Network error: {}", e)));
                 }
-                return Err(ApiError::Internal(anyhow::anyhow!("Network error: {}", e)));
-            }
-        };
+            };
 
-        let status = response.status();
-        
-        if status.is_success() {
-            let api_response = response.json::<AnthropicResponse>().await
-                .map_err(|e| {
-                    error!("Failed to parse Anthropic response: {}", e);
-                    ApiError::Internal(anyhow::anyhow!("Failed to parse response: {}", e))
-                })?;
-            
-            if let Some(usage) = &api_response.usage {
-                debug!("Token usage - Input: {}, Output: {}", usage.input_tokens, usage.output_tokens);
+            let status = response.status();
+
+            if status.is_success() {
+                let api_response = if request.stream {
+                    match self.read_stream(response).await {
+                        Ok(api_response) => api_response,
+                        Err(StreamFailure::Retryable(reason)) => {
+                            let delay = self.retry_policy.delay_for(attempt, None);
+                            if self.retry_policy.should_retry(attempt, retry_clock.spent() + attempt_started.elapsed(), delay) {
+                                self.wait_before_retry(&retry_clock, attempt, attempt_started, delay, reason).await;
+                                continue;
+                            }
+                            return Err(ApiError::Internal(anyhow::anyhow!("Streaming failed: {}", reason)));
+                        }
+                        Err(StreamFailure::Failed(e)) => return Err(e),
+                    }
+                } else {
+                    response.json::<AnthropicResponse>().await
+                        .map_err(|e| {
+                            error!("Failed to parse Anthropic response: {}", e);
+                            ApiError::Internal(anyhow::anyhow!("Failed to parse response: {}", e))
+                        })?
+                };
+
+                if let Some(usage) = &api_response.usage {
+                    debug!("Token usage - Input: {}, Output: {}", usage.input_tokens, usage.output_tokens);
+                    report_usage(*usage);
+                }
+
+                return Ok(api_response);
             }
-            
-            Ok(api_response)
-        } else {
+
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after);
             let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            
+
             error!("Anthropic API returned error status: {}", status);
             error!("Full error response: {}", error_text);
-            
-            match status {
-                StatusCode::UNAUTHORIZED => {
-                    error!("Invalid API key - check ANTHROPIC_API_KEY environment variable");
-                    Err(ApiError::BadRequest("Invalid API key".to_string()))
+
+            if RetryPolicy::is_retryable_status(status) {
+                let delay = self.retry_policy.delay_for(attempt, retry_after);
+                if self.retry_policy.should_retry(attempt, retry_clock.spent() + attempt_started.elapsed(), delay) {
+                    self.wait_before_retry(&retry_clock, attempt, attempt_started, delay, format!("status {}", status)).await;
+                    continue;
                 }
-                StatusCode::TOO_MANY_REQUESTS => {
-                    error!("Rate limit exceeded");
-                    if let Ok(error_detail) = serde_json::from_str::<AnthropicError>(&error_text) {
-                        error!("Rate limit details: {}", error_detail.error.message);
-                    }
-                    Err(ApiError::BadRequest("Rate limit exceeded. Please try again later.".to_string()))
+                warn!("Giving up on Anthropic API after {} attempts over {:?}", attempt, started.elapsed());
+            }
+
+            return Err(Self::error_from_response(status, &error_text));
+        }
+    }
+
+    /// Read a streamed response to the end, rebuilding the message it carries. A stream that
+    /// fails still reports the usage it carried up to that point.
+    async fn read_stream(&self, response: reqwest::Response) -> Result<AnthropicResponse, StreamFailure> {
+        let mut accumulator = StreamAccumulator::default();
+        if let Err(failure) = self.read_stream_events(response, &mut accumulator).await {
+            report_usage(accumulator.usage());
+            return Err(failure);
+        }
+
+        let usage = accumulator.usage();
+        accumulator.finish().map_err(|e| {
+            report_usage(usage);
+            StreamFailure::Failed(e)
+        })
+    }
+
+    async fn read_stream_events(&self, mut response: reqwest::Response, accumulator: &mut StreamAccumulator) -> Result<(), StreamFailure> {
+        let mut parser = SseParser::default();
+        let mut bytes = 0;
+
+        loop {
+            let chunk = match tokio::time::timeout(self.stall_timeout, response.chunk()).await {
+                Err(_) => {
+                    return Err(StreamFailure::Retryable(format!("stream stalled for {:?}", self.stall_timeout)));
                 }
-                StatusCode::BAD_REQUEST => {
-                    if let Ok(error_detail) = serde_json::from_str::<AnthropicError>(&error_text) {
-                        error!("Anthropic API error: {}", error_detail.error.message);
-                        error!("Error type: {:?}", error_detail.error.error_type);
-                        Err(ApiError::BadRequest(error_detail.error.message))
-                    } else {
-                        error!("Could not parse error response: {}", error_text);
-                        Err(ApiError::BadRequest(format!("Invalid request: {}", error_text)))
-                    }
+                Ok(Err(e)) => return Err(StreamFailure::Retryable(format!("stream interrupted: {}", e))),
+                Ok(Ok(None)) => break,
+                Ok(Ok(Some(chunk))) => chunk,
+            };
+
+            for payload in parser.push(&chunk) {
+                let event: StreamEvent = serde_json::from_str(&payload).map_err(|e| {
+                    error!("Failed to parse stream event {}: {}", payload, e);
+                    StreamFailure::Failed(ApiError::Internal(anyhow::anyhow!("Failed to parse stream event: {}", e)))
+                })?;
+                if let StreamEvent::Error { error } = &event {
+                    return Err(StreamFailure::Retryable(format!("stream error {}: {}", error.error_type, error.message)));
                 }
-                _ => {
-                    error!("Unexpected status code from Anthropic: {}", status);
-                    error!("Response body: {}", error_text);
-                    Err(ApiError::Internal(anyhow::anyhow!("AI service error ({}): {}", status, error_text)))
+
+                if let Some(delta) = accumulator.apply(event).map_err(StreamFailure::Failed)? {
+                    bytes += delta.len();
+                    notify_stream_progress(StreamProgress { bytes, delta });
                 }
             }
         }
+
+        if !accumulator.is_finished() {
+            return Err(StreamFailure::Retryable("stream ended before the message was complete".to_string()));
+        }
+        debug!("Streamed {} bytes of content", bytes);
+        Ok(())
+    }
+
+    /// Charge the failed attempt and the wait to the phase's retry clock, then wait.
+    async fn wait_before_retry(&self, retry_clock: &RetryClock, attempt: u32, attempt_started: Instant, delay: Duration, reason: String) {
+        warn!("Anthropic API attempt {} failed ({}), retrying in {:?}", attempt, reason, delay);
+        retry_clock.add(attempt_started.elapsed() + delay);
+        notify_retry(RetryNotice { attempt, delay, reason });
+        tokio::time::sleep(delay).await;
+    }
+
+    fn error_from_response(status: StatusCode, error_text: &str) -> ApiError {
+        match status {
+            StatusCode::UNAUTHORIZED => {
+                error!("Invalid API key - check ANTHROPIC_API_KEY environment variable");
+                ApiError::BadRequest("Invalid API key".to_string())
+            }
+            StatusCode::TOO_MANY_REQUESTS => {
+                error!("Rate limit exceeded");
+                if let Ok(error_detail) = serde_json::from_str::<AnthropicError>(error_text) {
+                    error!("Rate limit details: {}", error_detail.error.message);
+                }
+                ApiError::BadRequest("Rate limit exceeded. Please try again later.".to_string())
+            }
+            StatusCode::BAD_REQUEST => {
+                if let Ok(error_detail) = serde_json::from_str::<AnthropicError>(error_text) {
+                    error!("Anthropic API error: {}", error_detail.error.message);
+                    error!("Error type: {:?}", error_detail.error.error_type);
+                    ApiError::BadRequest(error_detail.error.message)
+                } else {
+                    error!("Could not parse error response: {}", error_text);
+                    ApiError::BadRequest(format!("Invalid request: {}", error_text))
+                }
+            }
+            _ => {
+                error!("Unexpected status code from Anthropic: {}", status);
+                error!("Response body: {}", error_text);
+                ApiError::Internal(anyhow::anyhow!("AI service error ({}): {}", status, error_text))
+            }
+        }
     }
 
     fn build_campaign_tool(&self, campaign: &Campaign) -> (String, Tool) {
@@ -398,4 +598,237 @@ Make sure all NPCs, locations, and quests are interconnected. Characters should
         (prompt, tool)
     }
 
-}
\ No newline at end of file
+}
+
+#[async_trait]
+impl LlmProvider for AnthropicClient {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    fn model(&self) -> &str {
+        MODEL
+    }
+
+    async fn generate_content(&self, prompt: &str, max_tokens: u32, temperature: f32) -> ApiResult<String> {
+        AnthropicClient::generate_content(self, prompt, max_tokens, temperature).await
+    }
+
+    async fn generate_with_tool_messages(
+        &self,
+        system: Option<&str>,
+        messages: Vec<Message>,
+        tool: Tool,
+        max_tokens: u32,
+        temperature: f32,
+    ) -> ApiResult<ToolCallResult> {
+        AnthropicClient::generate_with_tool_messages(self, system, messages, tool, max_tokens, temperature).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{RetryListener, UsageMeter, RETRY_LISTENER, USAGE_METER};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_multi_turn_request_serialization() {
+        let request = AnthropicRequest {
+            model: MODEL.to_string(),
+            max_tokens: 1000,
+            system: Some("You are a campaign designer.".to_string()),
+            messages: vec![
+                Message::user("Generate the world."),
+                Message::assistant_blocks(vec![Content::ToolUse {
+                    id: "toolu_1".to_string(),
+                    name: "generate_phase_1a".to_string(),
+                    input: json!({"planes": []}),
+                }]),
+                Message::user_blocks(vec![
+                    Content::ToolResult {
+                        tool_use_id: "toolu_1".to_string(),
+                        content: "planes must not be empty".to_string(),
+                        is_error: true,
+                    },
+                    Content::Text { text: "Try again.".to_string() },
+                ]),
+            ],
+            temperature: 0.7,
+            tools: None,
+            tool_choice: None,
+            stream: false,
+        };
+
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value["system"], "You are a campaign designer.");
+        assert_eq!(value["messages"][0]["content"], "Generate the world.");
+        assert_eq!(value["messages"][1]["content"][0]["type"], "tool_use");
+        assert_eq!(value["messages"][1]["content"][0]["id"], "toolu_1");
+        assert_eq!(value["messages"][2]["content"][0]["type"], "tool_result");
+        assert_eq!(value["messages"][2]["content"][0]["is_error"], true);
+        assert_eq!(value["messages"][2]["content"][1]["type"], "text");
+    }
+
+    #[test]
+    fn test_tool_result_omits_is_error_when_false() {
+        let block = Content::ToolResult { tool_use_id: "toolu_1".to_string(), content: "ok".to_string(), is_error: false };
+        let value = serde_json::to_value(&block).unwrap();
+        assert!(value.get("is_error").is_none());
+    }
+
+    const TOOL_STREAM: &str = concat!(
+        "event: message_start\n",
+        "data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"usage\":{\"input_tokens\":120,\"output_tokens\":1}}}\n\n",
+        "event: content_block_start\n",
+        "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"tool_use\",\"id\":\"toolu_1\",\"name\":\"generate_phase_1a\",\"input\":{}}}\n\n",
+        "event: content_block_delta\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"{\\\"planes\\\": []}\"}}\n\n",
+        "event: content_block_stop\n",
+        "data: {\"type\":\"content_block_stop\",\"index\":0}\n\n",
+        "event: message_delta\n",
+        "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"tool_use\"},\"usage\":{\"output_tokens\":42}}\n\n",
+        "event: message_stop\n",
+        "data: {\"type\":\"message_stop\"}\n\n",
+    );
+
+    /// How the test server answers one request.
+    enum Reply {
+        /// The whole stream
+        Complete,
+        /// The first event, then nothing while the connection stays open
+        Stall,
+        /// Everything before `message_stop`, then a clean end of body
+        Truncated,
+        /// An `error` event in place of the message
+        Overloaded,
+    }
+
+    /// Serve `replies` in order, one per connection, returning the URL to post to.
+    async fn serve(replies: Vec<Reply>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/v1/messages", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for reply in replies {
+                let (mut socket, _) = listener.accept().await.unwrap();
+
+                // Read the whole request so that replying never resets the connection
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request).to_lowercase();
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let length: usize = text
+                            .lines()
+                            .find_map(|line| line.strip_prefix("content-length:"))
+                            .map_or(0, |value| value.trim().parse().unwrap());
+                        if request.len() >= end + 4 + length {
+                            break;
+                        }
+                    }
+                }
+
+                let body = match reply {
+                    Reply::Complete => TOOL_STREAM.to_string(),
+                    Reply::Stall => TOOL_STREAM[..TOOL_STREAM.find("event: content_block_start").unwrap()].to_string(),
+                    Reply::Truncated => TOOL_STREAM[..TOOL_STREAM.find("event: message_stop").unwrap()].to_string(),
+                    Reply::Overloaded => "event: error\ndata: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}\n\n".to_string(),
+                };
+                let head = "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ntransfer-encoding: chunked\r\n\r\n";
+                let chunk = format!("{:x}\r\n{}\r\n", body.len(), body);
+                socket.write_all(head.as_bytes()).await.unwrap();
+                socket.write_all(chunk.as_bytes()).await.unwrap();
+                if let Reply::Stall = reply {
+                    tokio::spawn(async move {
+                        tokio::time::sleep(Duration::from_secs(30)).await;
+                        drop(socket);
+                    });
+                    continue;
+                }
+                socket.write_all(b"0\r\n\r\n").await.unwrap();
+            }
+        });
+        url
+    }
+
+    fn streaming_client(url: String, max_attempts: u32) -> AnthropicClient {
+        AnthropicClient {
+            api_url: url,
+            stall_timeout: Duration::from_millis(200),
+            ..AnthropicClient::new("test-key".to_string())
+                .with_streaming(true)
+                .with_retry_policy(RetryPolicy {
+                    max_attempts,
+                    base_delay: Duration::from_millis(10),
+                    max_delay: Duration::from_millis(10),
+                    max_total: Duration::from_secs(10),
+                })
+        }
+    }
+
+    fn streaming_request() -> AnthropicRequest {
+        AnthropicRequest {
+            model: MODEL.to_string(),
+            max_tokens: 1000,
+            system: None,
+            messages: vec![Message::user("Generate the world.")],
+            temperature: 0.7,
+            tools: None,
+            tool_choice: None,
+            stream: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stalled_truncated_and_failed_streams_are_retried() {
+        let url = serve(vec![Reply::Stall, Reply::Truncated, Reply::Overloaded, Reply::Complete]).await;
+        let reasons = Arc::new(Mutex::new(Vec::new()));
+        let listener: RetryListener = {
+            let reasons = reasons.clone();
+            Arc::new(move |notice: RetryNotice| reasons.lock().unwrap().push(notice.reason))
+        };
+
+        let response = RETRY_LISTENER
+            .scope(listener, streaming_client(url, 4).send_request(streaming_request()))
+            .await
+            .unwrap();
+
+        match &response.content[0] {
+            Content::ToolUse { input, .. } => assert_eq!(*input, json!({"planes": []})),
+            other => panic!("expected tool use, got {:?}", other),
+        }
+        let reasons = reasons.lock().unwrap();
+        assert_eq!(reasons.len(), 3);
+        assert!(reasons[0].starts_with("stream stalled"), "{}", reasons[0]);
+        assert!(reasons[1].starts_with("stream ended before the message was complete"), "{}", reasons[1]);
+        assert!(reasons[2].contains("overloaded_error"), "{}", reasons[2]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_that_keeps_stalling_gives_up() {
+        let url = serve(vec![Reply::Stall, Reply::Stall]).await;
+        let error = streaming_client(url, 2).send_request(streaming_request()).await.unwrap_err();
+        assert!(matches!(error, ApiError::Internal(_)), "{:?}", error);
+    }
+
+    #[tokio::test]
+    async fn test_failed_streams_report_the_usage_they_carried() {
+        let url = serve(vec![Reply::Stall, Reply::Truncated]).await;
+        let meter = Arc::new(UsageMeter::default());
+
+        let error = USAGE_METER
+            .scope(meter.clone(), streaming_client(url, 2).send_request(streaming_request()))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, ApiError::Internal(_)), "{:?}", error);
+        // Both attempts read message_start; only the truncated one got as far as message_delta
+        let usage = meter.reported();
+        assert_eq!(usage.input_tokens, 240);
+        assert_eq!(usage.output_tokens, 43);
+    }
+}
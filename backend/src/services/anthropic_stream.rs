@@ -0,0 +1,260 @@
+use crate::error::{ApiError, ApiResult};
+use crate::services::{AnthropicResponse, Content, ErrorDetail, Usage};
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// One event from a streamed Messages API response. Only the fields needed to rebuild the
+/// final message are kept; `ping` and anything newer than this client is ignored.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    MessageStart { message: StreamMessage },
+    ContentBlockStart { index: usize, content_block: Value },
+    ContentBlockDelta { index: usize, delta: StreamDelta },
+    ContentBlockStop { index: usize },
+    MessageDelta { usage: Option<StreamUsage> },
+    MessageStop,
+    Error { error: ErrorDetail },
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StreamMessage {
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StreamUsage {
+    pub output_tokens: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamDelta {
+    TextDelta { text: String },
+    InputJsonDelta { partial_json: String },
+    #[serde(other)]
+    Unknown,
+}
+
+/// Splits a byte stream into SSE `data:` payloads, holding back incomplete events (and
+/// multi-byte characters cut in half) until the rest of their bytes arrive.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+}
+
+impl SseParser {
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut payloads = Vec::new();
+        while let Some((end, separator)) = Self::event_end(&self.buffer) {
+            let event: Vec<u8> = self.buffer.drain(..end + separator).collect();
+            let event = String::from_utf8_lossy(&event[..end]);
+            let data: Vec<&str> = event
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|data| data.strip_prefix(' ').unwrap_or(data))
+                .collect();
+            if !data.is_empty() {
+                payloads.push(data.join("\n"));
+            }
+        }
+        payloads
+    }
+
+    /// Position and length of the first blank line ending an event.
+    fn event_end(buffer: &[u8]) -> Option<(usize, usize)> {
+        let lf = buffer.windows(2).position(|w| w == b"\n\n").map(|i| (i, 2));
+        let crlf = buffer.windows(4).position(|w| w == b"\r\n\r\n").map(|i| (i, 4));
+        match (lf, crlf) {
+            (Some(lf), Some(crlf)) => Some(if lf.0 < crlf.0 { lf } else { crlf }),
+            (lf, crlf) => lf.or(crlf),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum PartialBlock {
+    Text(String),
+    ToolUse { id: String, name: String, json: String },
+    Ignored,
+}
+
+/// Rebuilds the response a non-streamed request would have returned from stream events.
+#[derive(Debug, Default)]
+pub struct StreamAccumulator {
+    blocks: Vec<PartialBlock>,
+    usage: Usage,
+    finished: bool,
+}
+
+impl StreamAccumulator {
+    /// Apply one event, returning the text or partial JSON it added, if any.
+    pub fn apply(&mut self, event: StreamEvent) -> ApiResult<Option<String>> {
+        match event {
+            StreamEvent::MessageStart { message } => {
+                if let Some(usage) = message.usage {
+                    self.usage = usage;
+                }
+            }
+            StreamEvent::ContentBlockStart { index, content_block } => {
+                let block = match content_block.get("type").and_then(Value::as_str) {
+                    Some("text") => PartialBlock::Text(String::new()),
+                    Some("tool_use") => PartialBlock::ToolUse {
+                        id: content_block["id"].as_str().unwrap_or_default().to_string(),
+                        name: content_block["name"].as_str().unwrap_or_default().to_string(),
+                        json: String::new(),
+                    },
+                    _ => PartialBlock::Ignored,
+                };
+                if self.blocks.len() <= index {
+                    self.blocks.resize_with(index + 1, || PartialBlock::Ignored);
+                }
+                self.blocks[index] = block;
+            }
+            StreamEvent::ContentBlockDelta { index, delta } => {
+                match (self.blocks.get_mut(index), delta) {
+                    (Some(PartialBlock::Text(text)), StreamDelta::TextDelta { text: more }) => {
+                        text.push_str(&more);
+                        return Ok(Some(more));
+                    }
+                    (Some(PartialBlock::ToolUse { json, .. }), StreamDelta::InputJsonDelta { partial_json }) => {
+                        json.push_str(&partial_json);
+                        return Ok(Some(partial_json));
+                    }
+                    _ => {}
+                }
+            }
+            StreamEvent::MessageDelta { usage: Some(usage) } => {
+                self.usage.output_tokens = usage.output_tokens;
+            }
+            StreamEvent::MessageStop => self.finished = true,
+            // The reader retries on error events before they get here
+            StreamEvent::Error { .. }
+            | StreamEvent::ContentBlockStop { .. }
+            | StreamEvent::MessageDelta { usage: None }
+            | StreamEvent::Unknown => {}
+        }
+        Ok(None)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Usage reported so far: input tokens from `message_start`, output tokens from the
+    /// latest `message_delta`.
+    pub fn usage(&self) -> Usage {
+        self.usage
+    }
+
+    pub fn finish(self) -> ApiResult<AnthropicResponse> {
+        if !self.finished {
+            return Err(ApiError::Internal(anyhow::anyhow!("Stream ended before message_stop")));
+        }
+
+        let mut content = Vec::new();
+        for block in self.blocks {
+            match block {
+                PartialBlock::Text(text) => content.push(Content::Text { text }),
+                PartialBlock::ToolUse { id, name, json } => {
+                    let input = if json.trim().is_empty() {
+                        Value::Object(Default::default())
+                    } else {
+                        serde_json::from_str(&json).map_err(|e| {
+                            ApiError::BadRequest(format!("Streamed tool input is not valid JSON: {}", e))
+                        })?
+                    };
+                    content.push(Content::ToolUse { id, name, input });
+                }
+                PartialBlock::Ignored => {}
+            }
+        }
+
+        Ok(AnthropicResponse { content, usage: Some(self.usage) })
+    }
+}
+
+/// Progress of a streamed response, passed to whoever is listening for the current call.
+#[derive(Debug, Clone)]
+pub struct StreamProgress {
+    /// Bytes of text or tool input received so far
+    pub bytes: usize,
+    /// The text or partial JSON that just arrived
+    pub delta: String,
+}
+
+pub type StreamListener = Arc<dyn Fn(StreamProgress) + Send + Sync>;
+
+tokio::task_local! {
+    /// Set around an LLM call by a caller that wants streamed output as it arrives.
+    pub static STREAM_LISTENER: StreamListener;
+}
+
+pub fn notify_stream_progress(progress: StreamProgress) {
+    let _ = STREAM_LISTENER.try_with(|listener| listener(progress));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOOL_STREAM: &str = concat!(
+        "event: message_start\n",
+        "data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"usage\":{\"input_tokens\":120,\"output_tokens\":1}}}\n\n",
+        "event: content_block_start\n",
+        "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"tool_use\",\"id\":\"toolu_1\",\"name\":\"generate_phase_1a\",\"input\":{}}}\n\n",
+        "event: ping\n",
+        "data: {\"type\":\"ping\"}\n\n",
+        "event: content_block_delta\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"{\\\"planes\\\": [{\\\"na\"}}\n\n",
+        "event: content_block_delta\n",
+        "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"me\\\": \\\"Feywild\\\"}]}\"}}\n\n",
+        "event: content_block_stop\n",
+        "data: {\"type\":\"content_block_stop\",\"index\":0}\n\n",
+        "event: message_delta\n",
+        "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"tool_use\"},\"usage\":{\"output_tokens\":42}}\n\n",
+        "event: message_stop\n",
+        "data: {\"type\":\"message_stop\"}\n\n",
+    );
+
+    fn run(chunks: &[&[u8]]) -> ApiResult<AnthropicResponse> {
+        let mut parser = SseParser::default();
+        let mut accumulator = StreamAccumulator::default();
+        for chunk in chunks {
+            for payload in parser.push(chunk) {
+                accumulator.apply(serde_json::from_str(&payload)?)?;
+            }
+        }
+        accumulator.finish()
+    }
+
+    #[test]
+    fn test_accumulates_tool_input_across_arbitrary_chunk_boundaries() {
+        let bytes = TOOL_STREAM.as_bytes();
+        let chunks: Vec<&[u8]> = bytes.chunks(7).collect();
+        let response = run(&chunks).unwrap();
+
+        match &response.content[0] {
+            Content::ToolUse { id, name, input } => {
+                assert_eq!(id, "toolu_1");
+                assert_eq!(name, "generate_phase_1a");
+                assert_eq!(input["planes"][0]["name"], "Feywild");
+            }
+            other => panic!("expected tool use, got {:?}", other),
+        }
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.input_tokens, 120);
+        assert_eq!(usage.output_tokens, 42);
+    }
+
+    #[test]
+    fn test_truncated_stream_fails() {
+        let truncated = &TOOL_STREAM[..TOOL_STREAM.find("event: message_stop").unwrap()];
+        assert!(run(&[truncated.as_bytes()]).is_err());
+    }
+}
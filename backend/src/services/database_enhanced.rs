@@ -1,5 +1,6 @@
-use crate::error::ApiResult;
-use crate::models::Campaign;
+use crate::error::{ApiError, ApiResult};
+use crate::models::{Campaign, GenerationCall, NewGenerationCall, PhaseInfo, PhaseOutput, PhaseUsage};
+use crate::services::{ForeignKey, TableSummary, Usage};
 use sqlx::{PgPool, Transaction, Postgres, Row};
 use serde_json::{json, Value as JsonValue};
 use std::collections::HashMap;
@@ -78,6 +79,306 @@ impl DatabaseServiceEnhanced {
         Ok(())
     }
 
+    pub async fn update_campaign_status_cancelled(&self, campaign_id: i32) -> ApiResult<()> {
+        sqlx::query(
+            "UPDATE campaigns SET status = 'cancelled', current_phase_status = 'cancelled', updated_at = CURRENT_TIMESTAMP WHERE id = $1"
+        )
+        .bind(campaign_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_generation_resumed(&self, campaign_id: i32, progress: i32) -> ApiResult<()> {
+        sqlx::query(
+            "UPDATE campaigns SET status = 'generating', error_message = NULL, phase_progress = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1"
+        )
+        .bind(campaign_id)
+        .bind(progress)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Per-phase tracking methods (campaign_generation_phases)
+    pub async fn reset_phase_records(&self, campaign_id: i32, phase_names: &[String]) -> ApiResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO campaign_generation_phases (campaign_id, phase_name, status)
+            SELECT $1, phase_name, 'pending' FROM unnest($2::text[]) AS phase_name
+            ON CONFLICT (campaign_id, phase_name)
+            DO UPDATE SET status = 'pending', error_message = NULL, started_at = NULL, completed_at = NULL
+            "#
+        )
+        .bind(campaign_id)
+        .bind(phase_names)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_phase_running(&self, campaign_id: i32, phase_name: &str) -> ApiResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO campaign_generation_phases (campaign_id, phase_name, status, attempts, started_at)
+            VALUES ($1, $2, 'running', 1, CURRENT_TIMESTAMP)
+            ON CONFLICT (campaign_id, phase_name)
+            DO UPDATE SET status = 'running', attempts = campaign_generation_phases.attempts + 1,
+                error_message = NULL, started_at = CURRENT_TIMESTAMP, completed_at = NULL
+            "#
+        )
+        .bind(campaign_id)
+        .bind(phase_name)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_phase_completed(&self, campaign_id: i32, phase_name: &str) -> ApiResult<()> {
+        sqlx::query(
+            "UPDATE campaign_generation_phases SET status = 'completed', completed_at = CURRENT_TIMESTAMP WHERE campaign_id = $1 AND phase_name = $2"
+        )
+        .bind(campaign_id)
+        .bind(phase_name)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_phase_failed(&self, campaign_id: i32, phase_name: &str, error_msg: &str) -> ApiResult<()> {
+        sqlx::query(
+            "UPDATE campaign_generation_phases SET status = 'failed', error_message = $3 WHERE campaign_id = $1 AND phase_name = $2"
+        )
+        .bind(campaign_id)
+        .bind(phase_name)
+        .bind(error_msg)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_phases_stale(&self, campaign_id: i32, phase_names: &[String]) -> ApiResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO campaign_generation_phases (campaign_id, phase_name, status)
+            SELECT $1, phase_name, 'stale' FROM unnest($2::text[]) AS phase_name
+            ON CONFLICT (campaign_id, phase_name)
+            DO UPDATE SET status = 'stale'
+            "#
+        )
+        .bind(campaign_id)
+        .bind(phase_names)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_completed_phases(&self, campaign_id: i32) -> ApiResult<Vec<String>> {
+        let phases: Vec<String> = sqlx::query_scalar(
+            "SELECT phase_name FROM campaign_generation_phases WHERE campaign_id = $1 AND status = 'completed'"
+        )
+        .bind(campaign_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(phases)
+    }
+
+    pub async fn record_generation_usage(
+        &self,
+        campaign_id: i32,
+        phase_name: &str,
+        provider: &str,
+        model: &str,
+        usage: Usage,
+    ) -> ApiResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO generation_usage (campaign_id, phase_name, provider, model, input_tokens, output_tokens)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#
+        )
+        .bind(campaign_id)
+        .bind(phase_name)
+        .bind(provider)
+        .bind(model)
+        .bind(usage.input_tokens as i32)
+        .bind(usage.output_tokens as i32)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn record_repair_attempt(
+        &self,
+        campaign_id: i32,
+        phase_name: &str,
+        attempt: u32,
+        tool_name: &str,
+        tool_input: &JsonValue,
+        error: &ApiError,
+    ) -> ApiResult<()> {
+        let violations = match error {
+            ApiError::InvalidToolOutput { violations, .. } => json!(violations),
+            _ => json!([]),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO generation_repair_attempts
+                (campaign_id, phase_name, attempt, tool_name, tool_input, error_message, violations)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#
+        )
+        .bind(campaign_id)
+        .bind(phase_name)
+        .bind(attempt as i32)
+        .bind(tool_name)
+        .bind(tool_input)
+        .bind(error.to_string())
+        .bind(&violations)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn record_generation_call(&self, call: &NewGenerationCall) -> ApiResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO generation_calls
+                (campaign_id, phase_name, provider, model, system_prompt, messages, tool_name, tool_schema_hash,
+                 temperature, max_tokens, raw_response, error_message, input_tokens, output_tokens, duration_ms)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+            "#
+        )
+        .bind(call.campaign_id)
+        .bind(&call.phase_name)
+        .bind(&call.provider)
+        .bind(&call.model)
+        .bind(&call.system_prompt)
+        .bind(&call.messages)
+        .bind(&call.tool_name)
+        .bind(&call.tool_schema_hash)
+        .bind(call.temperature)
+        .bind(call.max_tokens as i32)
+        .bind(&call.raw_response)
+        .bind(&call.error_message)
+        .bind(call.input_tokens as i32)
+        .bind(call.output_tokens as i32)
+        .bind(call.duration_ms as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Logged LLM calls for a campaign, oldest first, optionally for a single phase.
+    pub async fn get_generation_calls(&self, campaign_id: i32, phase_name: Option<&str>) -> ApiResult<Vec<GenerationCall>> {
+        let calls = sqlx::query_as::<_, GenerationCall>(
+            r#"
+            SELECT * FROM generation_calls
+            WHERE campaign_id = $1 AND ($2::TEXT IS NULL OR phase_name = $2)
+            ORDER BY created_at, id
+            "#
+        )
+        .bind(campaign_id)
+        .bind(phase_name)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(calls)
+    }
+
+    /// Remember the tool input a phase was saved from, replacing any earlier output for the phase.
+    pub async fn record_phase_output(&self, campaign_id: i32, phase_name: &str, tool_name: &str, tool_input: &JsonValue) -> ApiResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO generation_phase_outputs (campaign_id, phase_name, tool_name, tool_input)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (campaign_id, phase_name) DO UPDATE
+            SET tool_name = EXCLUDED.tool_name, tool_input = EXCLUDED.tool_input, created_at = CURRENT_TIMESTAMP
+            "#
+        )
+        .bind(campaign_id)
+        .bind(phase_name)
+        .bind(tool_name)
+        .bind(tool_input)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_phase_outputs(&self, campaign_id: i32) -> ApiResult<Vec<PhaseOutput>> {
+        let outputs = sqlx::query_as::<_, PhaseOutput>(
+            "SELECT phase_name, tool_name, tool_input, created_at FROM generation_phase_outputs WHERE campaign_id = $1"
+        )
+        .bind(campaign_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(outputs)
+    }
+
+    pub async fn get_total_tokens(&self, campaign_id: i32) -> ApiResult<i64> {
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(input_tokens + output_tokens), 0)::BIGINT FROM generation_usage WHERE campaign_id = $1"
+        )
+        .bind(campaign_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(total)
+    }
+
+    /// Token totals per phase and model, in the order phases were first called.
+    pub async fn get_generation_usage(&self, campaign_id: i32) -> ApiResult<Vec<PhaseUsage>> {
+        let usage = sqlx::query_as::<_, PhaseUsage>(
+            r#"
+            SELECT phase_name, model, COUNT(*) AS calls,
+                   SUM(input_tokens)::BIGINT AS input_tokens, SUM(output_tokens)::BIGINT AS output_tokens
+            FROM generation_usage
+            WHERE campaign_id = $1
+            GROUP BY phase_name, model
+            ORDER BY MIN(created_at)
+            "#
+        )
+        .bind(campaign_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(usage)
+    }
+
+    /// Delete every row owned by the given phases so they can be generated again.
+    /// Phases and their tables are cleared in reverse order so that dependent rows go first.
+    pub async fn clear_phase_data(&self, campaign_id: i32, phases: &[PhaseInfo]) -> ApiResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for phase in phases.iter().rev() {
+            for table in phase.tables.iter().rev() {
+                // Table names come from PhaseInfo, never from user input
+                let result = sqlx::query(&format!("DELETE FROM {} WHERE campaign_id = $1", table))
+                    .bind(campaign_id)
+                    .execute(&mut *tx)
+                    .await?;
+                tracing::debug!("Cleared {} rows from {} for campaign {}", result.rows_affected(), table, campaign_id);
+            }
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
     pub async fn update_campaign_metadata(
         &self,
         tx: &mut Transaction<'_, Postgres>,
@@ -557,22 +858,38 @@ impl DatabaseServiceEnhanced {
                 .map(|s| s.to_string())
                 .collect::<Vec<String>>())
             .unwrap_or_else(Vec::new);
+
+        let equipment_proficiencies = class.get("equipment_proficiencies")
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| s.to_string())
+                .collect::<Vec<String>>())
+            .unwrap_or_else(Vec::new);
+
+        // Accept both 8 and "d8"
+        let hit_die = match class.get("hit_die") {
+            Some(JsonValue::Number(n)) => n.as_i64(),
+            Some(JsonValue::String(s)) => s.trim_start_matches(['d', 'D']).parse().ok(),
+            _ => None,
+        }.unwrap_or(8) as i32;
             
         let class_id: i32 = sqlx::query_scalar(
             r#"
-            INSERT INTO classes (campaign_id, name, description, hit_die, primary_ability, saving_throw_proficiencies, skill_proficiencies, equipment_proficiencies, class_features, spellcasting_ability)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            INSERT INTO character_classes (campaign_id, name, description, hit_die, primary_ability, saving_throw_proficiencies, skill_proficiencies, equipment_proficiencies, starting_equipment, class_features, spellcasting_ability)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             RETURNING id
             "#
         )
         .bind(campaign_id)
         .bind(class.get("name").and_then(|v| v.as_str()).unwrap_or("Unknown Class"))
         .bind(class.get("description").and_then(|v| v.as_str()).unwrap_or(""))
-        .bind(format!("d{}", class.get("hit_die").and_then(|v| v.as_i64()).unwrap_or(8)))
+        .bind(hit_die)
         .bind(&primary_abilities)
         .bind(&saving_throws)
         .bind(class.get("skill_proficiencies").unwrap_or(&json!({})))
-        .bind(class.get("equipment_proficiencies").or_else(|| class.get("starting_equipment")).unwrap_or(&json!({})))
+        .bind(&equipment_proficiencies)
+        .bind(class.get("starting_equipment").unwrap_or(&json!([])))
         .bind(class.get("class_features").unwrap_or(&json!({})))
         .bind(class.get("spellcasting_ability").and_then(|v| v.as_str()))
         .fetch_one(&mut **tx)
@@ -941,21 +1258,31 @@ impl DatabaseServiceEnhanced {
                 .map(|s| s.to_string())
                 .collect::<Vec<String>>())
             .unwrap_or_else(Vec::new);
+        let has_property = |name: &str| weapon_properties.iter().any(|p| p.eq_ignore_ascii_case(name) || p.replace('-', "_").eq_ignore_ascii_case(name));
         
         let weapon_id: i32 = sqlx::query_scalar(
             r#"
-            INSERT INTO weapons (item_id, weapon_category, damage_dice, damage_type, weapon_properties, range_normal, range_long)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO weapons (campaign_id, item_id, weapon_type, damage_dice, damage_type, weapon_properties, range_normal, range_long,
+                                 finesse, light, heavy, reach, thrown, two_handed, versatile, ammunition)
+            VALUES ((SELECT campaign_id FROM items WHERE id = $1), $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
             RETURNING id
             "#
         )
         .bind(item_id)
-        .bind(weapon_data.get("weapon_category").or_else(|| weapon_data.get("category")).and_then(|v| v.as_str()).unwrap_or("simple"))
+        .bind(weapon_data.get("weapon_type").or_else(|| weapon_data.get("weapon_category")).or_else(|| weapon_data.get("category")).and_then(|v| v.as_str()).unwrap_or("simple"))
         .bind(weapon_data.get("damage_dice").or_else(|| weapon_data.get("damage")).and_then(|v| v.as_str()))
         .bind(weapon_data.get("damage_type").and_then(|v| v.as_str()))
         .bind(&weapon_properties)
         .bind(weapon_data.get("range_normal").or_else(|| weapon_data.get("range")).and_then(|v| v.as_i64()).map(|v| v as i32))
         .bind(weapon_data.get("range_long").and_then(|v| v.as_i64()).map(|v| v as i32))
+        .bind(has_property("finesse"))
+        .bind(has_property("light"))
+        .bind(has_property("heavy"))
+        .bind(has_property("reach"))
+        .bind(has_property("thrown"))
+        .bind(has_property("two_handed"))
+        .bind(has_property("versatile"))
+        .bind(weapon_data.get("ammunition").and_then(|v| v.as_str()))
         .fetch_one(&mut **tx)
         .await?;
 
@@ -965,16 +1292,19 @@ impl DatabaseServiceEnhanced {
     pub async fn save_armor(&self, tx: &mut Transaction<'_, Postgres>, item_id: i32, armor_data: &JsonValue) -> ApiResult<i32> {
         let armor_id: i32 = sqlx::query_scalar(
             r#"
-            INSERT INTO armor (item_id, armor_category, armor_class, strength_requirement, stealth_disadvantage)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO armor (campaign_id, item_id, armor_type, armor_class, strength_requirement, stealth_disadvantage, dex_modifier_cap, don_time, doff_time)
+            VALUES ((SELECT campaign_id FROM items WHERE id = $1), $1, $2, $3, $4, $5, $6, $7, $8)
             RETURNING id
             "#
         )
         .bind(item_id)
-        .bind(armor_data.get("armor_category").or_else(|| armor_data.get("category")).and_then(|v| v.as_str()).unwrap_or("light"))
+        .bind(armor_data.get("armor_type").or_else(|| armor_data.get("armor_category")).or_else(|| armor_data.get("category")).and_then(|v| v.as_str()).unwrap_or("light"))
         .bind(armor_data.get("armor_class").or_else(|| armor_data.get("ac")).and_then(|v| v.as_i64()).map(|v| v as i32))
         .bind(armor_data.get("strength_requirement").or_else(|| armor_data.get("str_req")).and_then(|v| v.as_i64()).map(|v| v as i32))
         .bind(armor_data.get("stealth_disadvantage").and_then(|v| v.as_bool()).unwrap_or(false))
+        .bind(armor_data.get("dex_modifier_cap").and_then(|v| v.as_i64()).map(|v| v as i32))
+        .bind(armor_data.get("don_time").and_then(|v| v.as_str()))
+        .bind(armor_data.get("doff_time").and_then(|v| v.as_str()))
         .fetch_one(&mut **tx)
         .await?;
 
@@ -984,16 +1314,20 @@ impl DatabaseServiceEnhanced {
     pub async fn save_potion(&self, tx: &mut Transaction<'_, Postgres>, item_id: i32, potion_data: &JsonValue) -> ApiResult<i32> {
         let potion_id: i32 = sqlx::query_scalar(
             r#"
-            INSERT INTO potions (item_id, potion_type, duration, effects, side_effects)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO potions (campaign_id, item_id, potion_type, duration, effect, side_effects, onset_time, brewing_difficulty, ingredients, brewing_time)
+            VALUES ((SELECT campaign_id FROM items WHERE id = $1), $1, $2, $3, $4, $5, $6, $7, $8, $9)
             RETURNING id
             "#
         )
         .bind(item_id)
         .bind(potion_data.get("potion_type").or_else(|| potion_data.get("type")).and_then(|v| v.as_str()).unwrap_or("healing"))
         .bind(potion_data.get("duration").and_then(|v| v.as_str()))
-        .bind(potion_data.get("effects").unwrap_or(&json!([])))
+        .bind(potion_data.get("effect").or_else(|| potion_data.get("effects")).unwrap_or(&json!({})))
         .bind(potion_data.get("side_effects").unwrap_or(&json!([])))
+        .bind(potion_data.get("onset_time").and_then(|v| v.as_str()))
+        .bind(potion_data.get("brewing_difficulty").and_then(|v| v.as_str()))
+        .bind(potion_data.get("ingredients").unwrap_or(&json!([])))
+        .bind(potion_data.get("brewing_time").and_then(|v| v.as_str()))
         .fetch_one(&mut **tx)
         .await?;
 
@@ -1012,18 +1346,23 @@ impl DatabaseServiceEnhanced {
         
         let scroll_id: i32 = sqlx::query_scalar(
             r#"
-            INSERT INTO spell_scrolls (item_id, spell_name, spell_level, spell_school, casting_time, components, description)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO spell_scrolls (campaign_id, item_id, spell_name, spell_level, school_of_magic, casting_time, components, spell_description,
+                                       range_area, duration, save_dc, attack_bonus)
+            VALUES ((SELECT campaign_id FROM items WHERE id = $1), $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             RETURNING id
             "#
         )
         .bind(item_id)
         .bind(scroll_data.get("spell_name").or_else(|| scroll_data.get("name")).and_then(|v| v.as_str()).unwrap_or("Unknown Spell"))
         .bind(scroll_data.get("spell_level").or_else(|| scroll_data.get("level")).and_then(|v| v.as_i64()).unwrap_or(1) as i32)
-        .bind(scroll_data.get("spell_school").or_else(|| scroll_data.get("school")).and_then(|v| v.as_str()))
+        .bind(scroll_data.get("school_of_magic").or_else(|| scroll_data.get("spell_school")).or_else(|| scroll_data.get("school")).and_then(|v| v.as_str()))
         .bind(scroll_data.get("casting_time").and_then(|v| v.as_str()))
-        .bind(&components)
-        .bind(scroll_data.get("description").and_then(|v| v.as_str()))
+        .bind(json!(components))
+        .bind(scroll_data.get("spell_description").or_else(|| scroll_data.get("description")).and_then(|v| v.as_str()))
+        .bind(scroll_data.get("range_area").or_else(|| scroll_data.get("range")).and_then(|v| v.as_str()))
+        .bind(scroll_data.get("duration").and_then(|v| v.as_str()))
+        .bind(scroll_data.get("save_dc").and_then(|v| v.as_i64()).map(|v| v as i32))
+        .bind(scroll_data.get("attack_bonus").and_then(|v| v.as_i64()).map(|v| v as i32))
         .fetch_one(&mut **tx)
         .await?;
 
@@ -1058,8 +1397,9 @@ impl DatabaseServiceEnhanced {
         
         let sentient_id: i32 = sqlx::query_scalar(
             r#"
-            INSERT INTO sentient_items (item_id, intelligence, wisdom, charisma, alignment, communication_methods, senses, languages, personality, purpose, conflict_behavior)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            INSERT INTO sentient_items (campaign_id, item_id, intelligence, wisdom, charisma, alignment, communication_method, senses, languages,
+                                        personality_traits, purpose, conflict_resolution, ideals, bonds, flaws, ego_score, special_purpose, backstory)
+            VALUES ((SELECT campaign_id FROM items WHERE id = $1), $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
             RETURNING id
             "#
         )
@@ -1068,12 +1408,18 @@ impl DatabaseServiceEnhanced {
         .bind(sentient_data.get("wisdom").and_then(|v| v.as_i64()).unwrap_or(10) as i32)
         .bind(sentient_data.get("charisma").and_then(|v| v.as_i64()).unwrap_or(10) as i32)
         .bind(sentient_data.get("alignment").and_then(|v| v.as_str()))
-        .bind(&communication_methods)
-        .bind(&senses)
+        .bind(communication_methods.join(", "))
+        .bind(json!(senses))
         .bind(&languages)
-        .bind(sentient_data.get("personality").and_then(|v| v.as_str()))
+        .bind(sentient_data.get("personality_traits").or_else(|| sentient_data.get("personality")).unwrap_or(&json!([])))
         .bind(sentient_data.get("purpose").and_then(|v| v.as_str()))
-        .bind(sentient_data.get("conflict_behavior").and_then(|v| v.as_str()))
+        .bind(sentient_data.get("conflict_resolution").or_else(|| sentient_data.get("conflict_behavior")).unwrap_or(&json!({})))
+        .bind(sentient_data.get("ideals").unwrap_or(&json!([])))
+        .bind(sentient_data.get("bonds").unwrap_or(&json!([])))
+        .bind(sentient_data.get("flaws").unwrap_or(&json!([])))
+        .bind(sentient_data.get("ego_score").and_then(|v| v.as_i64()).map(|v| v as i32))
+        .bind(sentient_data.get("special_purpose").and_then(|v| v.as_str()))
+        .bind(sentient_data.get("backstory").and_then(|v| v.as_str()))
         .fetch_one(&mut **tx)
         .await?;
 
@@ -1282,8 +1628,8 @@ impl DatabaseServiceEnhanced {
         
         let creature_id: i32 = sqlx::query_scalar(
             r#"
-            INSERT INTO creatures (entity_id, creature_type, size, challenge_rating, abilities, habitat, behavior_patterns)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO creatures (campaign_id, entity_id, creature_type, size, challenge_rating, ability_scores, habitat, behavior)
+            VALUES ((SELECT campaign_id FROM entities WHERE id = $1), $1, $2, $3, $4, $5, $6, $7)
             RETURNING id
             "#
         )
@@ -1291,9 +1637,9 @@ impl DatabaseServiceEnhanced {
         .bind(creature_data.get("type").and_then(|v| v.as_str()).unwrap_or("beast"))
         .bind(creature_data.get("size").and_then(|v| v.as_str()).unwrap_or("medium"))
         .bind(creature_data.get("challenge_rating").and_then(|v| v.as_str()).unwrap_or("1").parse::<f32>().unwrap_or(1.0))
-        .bind(creature_data.get("abilities").or_else(|| creature_data.get("stats")).unwrap_or(&json!({})))
-        .bind(&habitat)
-        .bind(creature_data.get("behavior_patterns").or_else(|| creature_data.get("behavior")).and_then(|v| v.as_str()))
+        .bind(creature_data.get("ability_scores").or_else(|| creature_data.get("abilities")).or_else(|| creature_data.get("stats")).unwrap_or(&json!({})))
+        .bind(json!(habitat))
+        .bind(creature_data.get("behavior").or_else(|| creature_data.get("behavior_patterns")).unwrap_or(&json!({})))
         .fetch_one(&mut **tx)
         .await?;
 
@@ -1321,18 +1667,18 @@ impl DatabaseServiceEnhanced {
         
         let flora_id: i32 = sqlx::query_scalar(
             r#"
-            INSERT INTO flora (entity_id, plant_type, rarity, habitat, uses, harvesting_requirements, properties)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO flora (campaign_id, entity_id, plant_type, rarity, habitat, uses, harvest_difficulty, magical_properties)
+            VALUES ((SELECT campaign_id FROM entities WHERE id = $1), $1, $2, $3, $4, $5, $6, $7)
             RETURNING id
             "#
         )
         .bind(entity_id)
         .bind(flora_data.get("type").and_then(|v| v.as_str()).unwrap_or("plant"))
         .bind(flora_data.get("rarity").and_then(|v| v.as_str()).unwrap_or("common"))
-        .bind(&habitat)
-        .bind(&uses)
-        .bind(flora_data.get("harvesting_requirements").and_then(|v| v.as_str()))
-        .bind(flora_data.get("properties").unwrap_or(&json!({})))
+        .bind(json!(habitat))
+        .bind(json!(uses))
+        .bind(flora_data.get("harvest_difficulty").or_else(|| flora_data.get("harvesting_requirements")).and_then(|v| v.as_str()))
+        .bind(flora_data.get("magical_properties").or_else(|| flora_data.get("properties")).unwrap_or(&json!({})))
         .fetch_one(&mut **tx)
         .await?;
 
@@ -1351,29 +1697,64 @@ impl DatabaseServiceEnhanced {
         
         let fauna_id: i32 = sqlx::query_scalar(
             r#"
-            INSERT INTO fauna (entity_id, animal_type, size, habitat, diet, behavior, domestication_difficulty)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO fauna (campaign_id, entity_id, animal_type, size, habitat, diet, behavior, domestication_status)
+            VALUES ((SELECT campaign_id FROM entities WHERE id = $1), $1, $2, $3, $4, $5, $6, $7)
             RETURNING id
             "#
         )
         .bind(entity_id)
         .bind(fauna_data.get("animal_type").or_else(|| fauna_data.get("type")).and_then(|v| v.as_str()))
         .bind(fauna_data.get("size").and_then(|v| v.as_str()))
-        .bind(&habitat)
+        .bind(json!(habitat))
         .bind(fauna_data.get("diet").and_then(|v| v.as_str()))
-        .bind(fauna_data.get("behavior").and_then(|v| v.as_str()))
-        .bind(fauna_data.get("domestication_difficulty").and_then(|v| v.as_str()))
+        .bind(fauna_data.get("behavior").unwrap_or(&json!({})))
+        .bind(fauna_data.get("domestication_status").or_else(|| fauna_data.get("domestication_difficulty")).and_then(|v| v.as_str()))
         .fetch_one(&mut **tx)
         .await?;
 
         Ok(fauna_id)
     }
 
+    /// Save a quest as a quest hook, linking the NPCs and location it names through the ids
+    /// saved earlier in the same transaction. Names with no saved row are dropped.
     pub async fn save_quest(&self, tx: &mut Transaction<'_, Postgres>, campaign_id: i32, quest_data: &JsonValue, entity_mapping: &HashMap<String, i32>, location_mapping: &HashMap<String, i32>) -> ApiResult<i32> {
-        // WARNING: quests table does not exist in database schema
-        // This method is a no-op to prevent errors - table needs to be created
-        tracing::warn!("save_quest called but quests table does not exist");
-        Ok(1)
+        let mut related_entities: Vec<i32> = quest_data.get("quest_giver")
+            .into_iter()
+            .chain(quest_data.get("connected_npcs").and_then(|v| v.as_array()).into_iter().flatten())
+            .filter_map(|v| v.as_str())
+            .filter_map(|name| entity_mapping.get(name).copied())
+            .collect();
+        related_entities.sort_unstable();
+        related_entities.dedup();
+
+        let related_locations: Vec<i32> = quest_data.get("location")
+            .and_then(|v| v.as_str())
+            .and_then(|name| location_mapping.get(name).copied())
+            .into_iter()
+            .collect();
+
+        let reward = quest_data.get("rewards")
+            .and_then(|v| v.as_array())
+            .map(|rewards| rewards.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join(", "));
+
+        let quest_id: i32 = sqlx::query_scalar(
+            r#"
+            INSERT INTO quest_hooks (campaign_id, title, description, difficulty, reward, related_entity_ids, related_location_ids)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id
+            "#
+        )
+        .bind(campaign_id)
+        .bind(quest_data.get("title").and_then(|v| v.as_str()).unwrap_or("Untitled Quest"))
+        .bind(quest_data.get("description").and_then(|v| v.as_str()))
+        .bind(quest_data.get("difficulty").and_then(|v| v.as_str()).unwrap_or("medium"))
+        .bind(reward)
+        .bind(&related_entities)
+        .bind(&related_locations)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(quest_id)
     }
 
     pub async fn save_faction_relationship(&self, tx: &mut Transaction<'_, Postgres>, faction_a_id: i32, faction_b_id: i32, relationship: &JsonValue) -> ApiResult<i32> {
@@ -1404,30 +1785,31 @@ impl DatabaseServiceEnhanced {
     }
 
     pub async fn save_location_connection(&self, tx: &mut Transaction<'_, Postgres>, location_a_id: i32, location_b_id: i32, connection: &JsonValue) -> ApiResult<i32> {
-        // Convert dangers from JSON array to string array (note: column is 'dangers' not 'hazards')
-        let dangers = connection.get("dangers")
-            .or_else(|| connection.get("hazards"))
-            .and_then(|v| v.as_array())
-            .map(|arr| arr.iter()
-                .filter_map(|v| v.as_str())
-                .map(|s| s.to_string())
-                .collect::<Vec<String>>())
-            .unwrap_or_else(Vec::new);
+        // Travel time may come as a free-form string or as a number of hours
+        let travel_time = match connection.get("travel_time").or_else(|| connection.get("travel_time_hours")) {
+            Some(JsonValue::String(text)) => Some(text.clone()),
+            Some(JsonValue::Number(hours)) => Some(format!("{} hours", hours)),
+            _ => None,
+        };
         
         let conn_id: i32 = sqlx::query_scalar(
             r#"
-            INSERT INTO location_connections (from_location_id, to_location_id, connection_type, travel_time_hours, difficulty, dangers, description)
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            INSERT INTO location_connections (campaign_id, from_location_id, to_location_id, connection_type, travel_time, difficulty, hazards, description,
+                                              distance, landmarks, cost)
+            VALUES ((SELECT campaign_id FROM locations WHERE id = $1), $1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             RETURNING id
             "#
         )
         .bind(location_a_id)
         .bind(location_b_id)
         .bind(connection.get("connection_type").or_else(|| connection.get("type")).and_then(|v| v.as_str()).unwrap_or("road"))
-        .bind(connection.get("travel_time_hours").or_else(|| connection.get("travel_time")).and_then(|v| v.as_f64()))
+        .bind(travel_time)
         .bind(connection.get("difficulty").and_then(|v| v.as_str()).unwrap_or("normal"))
-        .bind(&dangers)
+        .bind(connection.get("hazards").or_else(|| connection.get("dangers")).unwrap_or(&json!([])))
         .bind(connection.get("description").and_then(|v| v.as_str()))
+        .bind(connection.get("distance").and_then(|v| v.as_f64()).map(|v| v as f32))
+        .bind(connection.get("landmarks").unwrap_or(&json!([])))
+        .bind(connection.get("cost").and_then(|v| v.as_i64()).map(|v| v as i32))
         .fetch_one(&mut **tx)
         .await?;
 
@@ -1819,6 +2201,65 @@ impl DatabaseServiceEnhanced {
     }
 
     // Context building methods for phase-to-phase data passing
+    /// Id and name (or title) of every row the given tables hold for a campaign, one summary
+    /// per table in the order given. Rows without a name are left out.
+    pub async fn get_table_summaries(&self, campaign_id: i32, tables: &[String]) -> ApiResult<Vec<TableSummary>> {
+        if tables.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Table names come from PhaseInfo, never from user input
+        let query = tables
+            .iter()
+            .map(|table| {
+                format!(
+                    "SELECT '{0}' AS table_name, t.id, COALESCE(to_jsonb(t)->>'name', to_jsonb(t)->>'title') AS name \
+                     FROM {0} t WHERE t.campaign_id = $1",
+                    table
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" UNION ALL ");
+
+        let rows = sqlx::query_as::<_, (String, i32, Option<String>)>(&format!("{} ORDER BY 2", query))
+            .bind(campaign_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut summaries: Vec<TableSummary> = tables
+            .iter()
+            .map(|table| TableSummary { table: table.clone(), entries: Vec::new() })
+            .collect();
+        for (table, id, name) in rows {
+            if let (Some(summary), Some(name)) = (summaries.iter_mut().find(|s| s.table == table), name) {
+                summary.entries.push((id, name));
+            }
+        }
+
+        Ok(summaries)
+    }
+
+    /// Every foreign key in the public schema, for resolving model-provided references.
+    pub async fn get_foreign_keys(&self) -> ApiResult<Vec<ForeignKey>> {
+        let foreign_keys = sqlx::query_as::<_, ForeignKey>(
+            r#"
+            SELECT kcu.table_name::TEXT AS table_name,
+                   kcu.column_name::TEXT AS column_name,
+                   ccu.table_name::TEXT AS referenced_table
+            FROM information_schema.table_constraints tc
+            JOIN information_schema.key_column_usage kcu
+              ON kcu.constraint_name = tc.constraint_name AND kcu.table_schema = tc.table_schema
+            JOIN information_schema.constraint_column_usage ccu
+              ON ccu.constraint_name = tc.constraint_name AND ccu.table_schema = tc.table_schema
+            WHERE tc.constraint_type = 'FOREIGN KEY' AND tc.table_schema = 'public'
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(foreign_keys)
+    }
+
     pub async fn get_phase_1a_context(&self, _campaign_id: i32) -> ApiResult<JsonValue> {
         // Phase 1A is the first phase, no prior context needed
         Ok(json!({}))
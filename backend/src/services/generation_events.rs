@@ -0,0 +1,145 @@
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// Events buffered per subscriber before slow listeners start missing them.
+pub const GENERATION_EVENT_CAPACITY: usize = 1024;
+
+pub type GenerationEventSender = broadcast::Sender<GenerationEvent>;
+
+/// Progress events emitted while a campaign is generated, streamed to the frontend over SSE.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GenerationEvent {
+    PhaseStarted {
+        campaign_id: i32,
+        phase: String,
+        phase_number: i32,
+    },
+    PhaseCompleted {
+        campaign_id: i32,
+        phase: String,
+        phase_number: i32,
+        progress: i32,
+    },
+    RowsSaved {
+        campaign_id: i32,
+        phase: String,
+        table: String,
+        rows: usize,
+    },
+    TokenUsage {
+        campaign_id: i32,
+        phase: String,
+        input_tokens: u32,
+        output_tokens: u32,
+    },
+    LlmRetry {
+        campaign_id: i32,
+        phase: String,
+        attempt: u32,
+        delay_ms: u64,
+        reason: String,
+    },
+    /// Output received so far from a streamed LLM call.
+    LlmProgress {
+        campaign_id: i32,
+        phase: String,
+        bytes: usize,
+        estimated_tokens: usize,
+    },
+    /// A phase's output was rejected and sent back to the model for correction.
+    RepairAttempt {
+        campaign_id: i32,
+        phase: String,
+        attempt: u32,
+        reason: String,
+    },
+    Error {
+        campaign_id: i32,
+        phase: Option<String>,
+        message: String,
+    },
+    GenerationCompleted {
+        campaign_id: i32,
+    },
+    GenerationCancelled {
+        campaign_id: i32,
+    },
+}
+
+impl GenerationEvent {
+    pub fn campaign_id(&self) -> i32 {
+        match self {
+            GenerationEvent::PhaseStarted { campaign_id, .. }
+            | GenerationEvent::PhaseCompleted { campaign_id, .. }
+            | GenerationEvent::RowsSaved { campaign_id, .. }
+            | GenerationEvent::TokenUsage { campaign_id, .. }
+            | GenerationEvent::LlmRetry { campaign_id, .. }
+            | GenerationEvent::LlmProgress { campaign_id, .. }
+            | GenerationEvent::RepairAttempt { campaign_id, .. }
+            | GenerationEvent::Error { campaign_id, .. }
+            | GenerationEvent::GenerationCompleted { campaign_id }
+            | GenerationEvent::GenerationCancelled { campaign_id } => *campaign_id,
+        }
+    }
+
+    /// SSE event name, matching the serialized `type` tag.
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            GenerationEvent::PhaseStarted { .. } => "phase_started",
+            GenerationEvent::PhaseCompleted { .. } => "phase_completed",
+            GenerationEvent::RowsSaved { .. } => "rows_saved",
+            GenerationEvent::TokenUsage { .. } => "token_usage",
+            GenerationEvent::LlmRetry { .. } => "llm_retry",
+            GenerationEvent::LlmProgress { .. } => "llm_progress",
+            GenerationEvent::RepairAttempt { .. } => "repair_attempt",
+            GenerationEvent::Error { .. } => "error",
+            GenerationEvent::GenerationCompleted { .. } => "generation_completed",
+            GenerationEvent::GenerationCancelled { .. } => "generation_cancelled",
+        }
+    }
+
+    /// One `RowsSaved` event per table, from the table names returned by the GraphQL save methods.
+    pub fn rows_saved(campaign_id: i32, phase: &str, saved_tables: &[String]) -> Vec<GenerationEvent> {
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for table in saved_tables {
+            match counts.iter_mut().find(|(name, _)| name == table) {
+                Some((_, rows)) => *rows += 1,
+                None => counts.push((table, 1)),
+            }
+        }
+
+        counts
+            .into_iter()
+            .map(|(table, rows)| GenerationEvent::RowsSaved {
+                campaign_id,
+                phase: phase.to_string(),
+                table: table.to_string(),
+                rows,
+            })
+            .collect()
+    }
+}
+
+pub fn generation_event_channel() -> GenerationEventSender {
+    broadcast::channel(GENERATION_EVENT_CAPACITY).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_saved_groups_by_table() {
+        let saved = vec!["races".to_string(), "feats".to_string(), "races".to_string()];
+        let events = GenerationEvent::rows_saved(7, "phase_1b_character_building", &saved);
+
+        assert_eq!(events.len(), 2);
+        let json = serde_json::to_value(&events[0]).unwrap();
+        assert_eq!(json["type"], "rows_saved");
+        assert_eq!(json["table"], "races");
+        assert_eq!(json["rows"], 2);
+        assert_eq!(events[0].event_name(), "rows_saved");
+        assert_eq!(events[1].campaign_id(), 7);
+    }
+}
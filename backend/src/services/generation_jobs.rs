@@ -0,0 +1,457 @@
+use crate::error::{ApiError, ApiResult};
+use crate::models::{GenerationJob, GenerationJobKind};
+use crate::services::{DatabaseServiceEnhanced, GenerationServiceEnhanced};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// How long a claimed job stays leased to a worker without a heartbeat.
+const LEASE_SECONDS: f64 = 120.0;
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// What a heartbeat found out about a running job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lease {
+    Held,
+    CancelRequested,
+    /// The lease expired and the job was claimed by another worker
+    Lost,
+}
+
+/// Postgres-backed queue of generation jobs. Jobs survive backend restarts: a job whose
+/// worker disappears keeps its `running` state until the lease expires, then gets claimed
+/// again and resumed from the first incomplete phase.
+pub struct GenerationJobQueue {
+    pool: PgPool,
+    database: Arc<DatabaseServiceEnhanced>,
+    generation: Arc<GenerationServiceEnhanced>,
+}
+
+impl GenerationJobQueue {
+    pub fn new(
+        pool: PgPool,
+        database: Arc<DatabaseServiceEnhanced>,
+        generation: Arc<GenerationServiceEnhanced>,
+    ) -> Self {
+        Self {
+            pool,
+            database,
+            generation,
+        }
+    }
+
+    pub async fn enqueue(&self, campaign_id: i32, kind: GenerationJobKind) -> ApiResult<GenerationJob> {
+        let (phase_name, cascade_mode) = match &kind {
+            GenerationJobKind::Regenerate { phase, cascade } => (Some(phase.as_str()), Some(cascade.as_str())),
+            _ => (None, None),
+        };
+        let source_campaign_id = match &kind {
+            GenerationJobKind::Replay { source_campaign_id } => Some(*source_campaign_id),
+            _ => None,
+        };
+
+        let result = sqlx::query_as::<_, GenerationJob>(
+            r#"
+            INSERT INTO generation_jobs (campaign_id, kind, phase_name, cascade_mode, source_campaign_id)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#
+        )
+        .bind(campaign_id)
+        .bind(kind.as_str())
+        .bind(phase_name)
+        .bind(cascade_mode)
+        .bind(source_campaign_id)
+        .fetch_one(&self.pool)
+        .await;
+
+        match result {
+            Ok(job) => {
+                info!("Queued {} job {} for campaign {}", job.kind, job.id, campaign_id);
+                Ok(job)
+            }
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(ApiError::BadRequest(
+                "Campaign already has a generation job in progress".to_string(),
+            )),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Claim the oldest pending job, or a running job whose lease has expired.
+    async fn claim_next(&self, worker_id: &str) -> ApiResult<Option<GenerationJob>> {
+        let job = sqlx::query_as::<_, GenerationJob>(
+            r#"
+            UPDATE generation_jobs
+            SET state = 'running', attempts = attempts + 1, worker_id = $1,
+                lease_expires_at = CURRENT_TIMESTAMP + make_interval(secs => $2)
+            WHERE id = (
+                SELECT id FROM generation_jobs
+                WHERE state = 'pending' OR (state = 'running' AND lease_expires_at < CURRENT_TIMESTAMP)
+                ORDER BY created_at, id
+                FOR UPDATE SKIP LOCKED
+                LIMIT 1
+            )
+            RETURNING *
+            "#
+        )
+        .bind(worker_id)
+        .bind(LEASE_SECONDS)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    /// Extend the lease on a running job held by `worker_id`.
+    async fn renew_lease(&self, job_id: i32, worker_id: &str) -> ApiResult<Lease> {
+        let cancel_requested: Option<bool> = sqlx::query_scalar(
+            r#"
+            UPDATE generation_jobs SET lease_expires_at = CURRENT_TIMESTAMP + make_interval(secs => $3)
+            WHERE id = $1 AND worker_id = $2
+            RETURNING cancel_requested
+            "#
+        )
+        .bind(job_id)
+        .bind(worker_id)
+        .bind(LEASE_SECONDS)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(match cancel_requested {
+            Some(true) => Lease::CancelRequested,
+            Some(false) => Lease::Held,
+            None => Lease::Lost,
+        })
+    }
+
+    /// Cancel a campaign's active job. A pending job is cancelled outright; a running job is
+    /// flagged so the worker holding it stops, immediately if it runs in this process or on its
+    /// next heartbeat otherwise.
+    pub async fn request_cancel(&self, campaign_id: i32) -> ApiResult<GenerationJob> {
+        let pending = sqlx::query_as::<_, GenerationJob>(
+            "UPDATE generation_jobs SET state = 'cancelled', cancel_requested = true WHERE campaign_id = $1 AND state = 'pending' RETURNING *"
+        )
+        .bind(campaign_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if let Some(job) = pending {
+            self.database.update_campaign_status_cancelled(campaign_id).await?;
+            info!("Cancelled queued job {} for campaign {}", job.id, campaign_id);
+            return Ok(job);
+        }
+
+        let running = sqlx::query_as::<_, GenerationJob>(
+            "UPDATE generation_jobs SET cancel_requested = true WHERE campaign_id = $1 AND state = 'running' RETURNING *"
+        )
+        .bind(campaign_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Campaign has no generation in progress".to_string()))?;
+
+        if !self.generation.cancel_generation(campaign_id) {
+            info!("Job {} for campaign {} runs elsewhere; it will stop on its next heartbeat", running.id, campaign_id);
+        }
+
+        Ok(running)
+    }
+
+    /// Record a job's outcome, provided `worker_id` still holds it. Returns false when the lease
+    /// was lost to another worker, whose run the outcome must not overwrite.
+    async fn finish(&self, job_id: i32, worker_id: &str, state: &str, error_msg: Option<&str>) -> ApiResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE generation_jobs SET state = $2, error_message = $3, lease_expires_at = NULL
+            WHERE id = $1 AND worker_id = $4 AND state = 'running'
+            "#
+        )
+        .bind(job_id)
+        .bind(state)
+        .bind(error_msg)
+        .bind(worker_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            warn!("Worker {} no longer holds job {}; not recording it as {}", worker_id, job_id, state);
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Poll for jobs forever under a fresh worker id. Started from `main.rs` once per worker;
+    /// any orphaned jobs are picked up on boot once their lease runs out.
+    pub async fn run_worker(self: Arc<Self>) {
+        let worker_id = format!("worker-{}", uuid::Uuid::new_v4());
+        info!("Generation worker {} started", worker_id);
+
+        loop {
+            match self.claim_next(&worker_id).await {
+                Ok(Some(job)) => self.process(job, &worker_id).await,
+                Ok(None) => tokio::time::sleep(POLL_INTERVAL).await,
+                Err(e) => {
+                    error!("Failed to claim generation job: {}", e);
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    async fn process(&self, job: GenerationJob, worker_id: &str) {
+        if job.attempts > job.max_attempts {
+            let message = format!("Generation abandoned after {} interrupted attempts", job.max_attempts);
+            warn!("Job {} for campaign {}: {}", job.id, job.campaign_id, message);
+            match self.finish(job.id, worker_id, "failed", Some(&message)).await {
+                Ok(true) => {
+                    if let Err(e) = self.database.update_campaign_status_with_error(job.campaign_id, &message).await {
+                        error!("Failed to mark campaign {} as errored: {}", job.campaign_id, e);
+                    }
+                }
+                Ok(false) => {}
+                Err(e) => error!("Failed to record failure of job {}: {}", job.id, e),
+            }
+            return;
+        }
+
+        // Cancelled while the worker that held it was gone
+        if job.cancel_requested {
+            info!("Job {} for campaign {} was cancelled before it could be recovered", job.id, job.campaign_id);
+            match self.finish(job.id, worker_id, "cancelled", None).await {
+                Ok(true) => {
+                    if let Err(e) = self.database.update_campaign_status_cancelled(job.campaign_id).await {
+                        error!("Failed to mark campaign {} as cancelled: {}", job.campaign_id, e);
+                    }
+                }
+                Ok(false) => {}
+                Err(e) => error!("Failed to record cancellation of job {}: {}", job.id, e),
+            }
+            return;
+        }
+
+        info!("Worker {} running {} job {} for campaign {} (attempt {})", worker_id, job.kind, job.id, job.campaign_id, job.attempts);
+
+        let work = self.execute(&job);
+        tokio::pin!(work);
+
+        // Keep the lease alive for as long as the job is running
+        let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+        heartbeat.tick().await;
+        let result = loop {
+            tokio::select! {
+                result = &mut work => break result,
+                _ = heartbeat.tick() => {
+                    match self.renew_lease(job.id, worker_id).await {
+                        Ok(Lease::Held) => {}
+                        Ok(Lease::CancelRequested) => {
+                            self.generation.cancel_generation(job.campaign_id);
+                        }
+                        // Another worker owns the job now. Dropping `work` stops this run
+                        // where it is, without rolling back or recording an outcome that
+                        // would clobber the new run
+                        Ok(Lease::Lost) => {
+                            warn!("Worker {} lost the lease on job {} for campaign {}; abandoning its run", worker_id, job.id, job.campaign_id);
+                            return;
+                        }
+                        Err(e) => warn!("Failed to renew lease on job {}: {}", job.id, e),
+                    }
+                }
+            }
+        };
+
+        let finished = match &result {
+            Ok(()) => self.finish(job.id, worker_id, "completed", None).await,
+            Err(ApiError::Cancelled) => self.finish(job.id, worker_id, "cancelled", None).await,
+            Err(e) => {
+                error!("Generation job {} for campaign {} failed: {}", job.id, job.campaign_id, e);
+                self.finish(job.id, worker_id, "failed", Some(&e.to_string())).await
+            }
+        };
+        if let Err(e) = finished {
+            error!("Failed to record outcome of job {}: {}", job.id, e);
+        }
+    }
+
+    async fn execute(&self, job: &GenerationJob) -> ApiResult<()> {
+        let kind = job.job_kind().map_err(ApiError::BadRequest)?;
+
+        // A job claimed again after its lease expired was interrupted mid-run
+        if job.attempts > 1 {
+            info!("Recovering interrupted job {} for campaign {}", job.id, job.campaign_id);
+            self.database
+                .update_campaign_status_with_error(job.campaign_id, "Generation was interrupted and is being resumed")
+                .await?;
+
+            return match kind {
+                GenerationJobKind::Regenerate { phase, cascade } => {
+                    self.generation.regenerate_phase(job.campaign_id, &phase, cascade).await
+                }
+                GenerationJobKind::Generate | GenerationJobKind::Resume => {
+                    self.generation.resume_campaign_generation(job.campaign_id).await
+                }
+                // Replays start over; they cost no tokens
+                GenerationJobKind::Replay { source_campaign_id } => {
+                    self.generation.replay_campaign_generation(job.campaign_id, source_campaign_id).await
+                }
+            };
+        }
+
+        match kind {
+            GenerationJobKind::Generate => self.generation.generate_campaign_content(job.campaign_id).await,
+            GenerationJobKind::Resume => self.generation.resume_campaign_generation(job.campaign_id).await,
+            GenerationJobKind::Regenerate { phase, cascade } => {
+                self.generation.regenerate_phase(job.campaign_id, &phase, cascade).await
+            }
+            GenerationJobKind::Replay { source_campaign_id } => {
+                self.generation.replay_campaign_generation(job.campaign_id, source_campaign_id).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::{generation_event_channel, GraphQLClient, HasuraSchemaGenerator, MockLlmProvider};
+    use std::collections::HashMap;
+    use tokio::sync::{Mutex, RwLock};
+
+    /// `claim_next` takes the oldest claimable job in the table, so tests sharing a database
+    /// must not claim concurrently.
+    static DATABASE_LOCK: Mutex<()> = Mutex::const_new(());
+
+    /// A queue over Postgres at `DATABASE_URL`, or `None` to skip the test. Jobs are only
+    /// claimed and finished here, so the generation service never reaches Hasura or an LLM.
+    async fn queue() -> Option<GenerationJobQueue> {
+        let Ok(database_url) = std::env::var("DATABASE_URL") else {
+            println!("DATABASE_URL not set, skipping test");
+            return None;
+        };
+        let pool = crate::db::create_pool(&database_url).await.expect("migrated database");
+        let database = Arc::new(DatabaseServiceEnhanced::new(pool.clone()));
+        let graphql = GraphQLClient::new("unused".to_string());
+        let generation = Arc::new(GenerationServiceEnhanced::new(
+            database.clone(),
+            Arc::new(graphql.clone()),
+            Arc::new(MockLlmProvider::new(HashMap::new())),
+            Arc::new(RwLock::new(HasuraSchemaGenerator::new(graphql))),
+            1,
+            generation_event_channel(),
+        ));
+        Some(GenerationJobQueue::new(pool, database, generation))
+    }
+
+    async fn enqueue_for_new_campaign(queue: &GenerationJobQueue, name: &str) -> GenerationJob {
+        let campaign_id: i32 = sqlx::query_scalar("INSERT INTO campaigns (name) VALUES ($1) RETURNING id")
+            .bind(name)
+            .fetch_one(&queue.pool)
+            .await
+            .unwrap();
+        queue.enqueue(campaign_id, GenerationJobKind::Generate).await.unwrap()
+    }
+
+    async fn claim(queue: &GenerationJobQueue, worker_id: &str) -> GenerationJob {
+        queue.claim_next(worker_id).await.unwrap().expect("a claimable job")
+    }
+
+    async fn expire_lease(queue: &GenerationJobQueue, job_id: i32) {
+        sqlx::query("UPDATE generation_jobs SET lease_expires_at = CURRENT_TIMESTAMP - INTERVAL '1 second' WHERE id = $1")
+            .bind(job_id)
+            .execute(&queue.pool)
+            .await
+            .unwrap();
+    }
+
+    async fn reload(queue: &GenerationJobQueue, job_id: i32) -> GenerationJob {
+        sqlx::query_as("SELECT * FROM generation_jobs WHERE id = $1")
+            .bind(job_id)
+            .fetch_one(&queue.pool)
+            .await
+            .unwrap()
+    }
+
+    async fn delete_campaign(queue: &GenerationJobQueue, campaign_id: i32) {
+        sqlx::query("DELETE FROM campaigns WHERE id = $1")
+            .bind(campaign_id)
+            .execute(&queue.pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pending_job_is_claimed() {
+        let _lock = DATABASE_LOCK.lock().await;
+        let Some(queue) = queue().await else {
+            return;
+        };
+        let job = enqueue_for_new_campaign(&queue, "Job Claim Test").await;
+
+        let claimed = claim(&queue, "worker-a").await;
+        let second = queue.claim_next("worker-b").await.unwrap();
+
+        delete_campaign(&queue, job.campaign_id).await;
+
+        assert_eq!(claimed.id, job.id);
+        assert_eq!(claimed.state, "running");
+        assert_eq!(claimed.attempts, 1);
+        assert_eq!(claimed.worker_id.as_deref(), Some("worker-a"));
+        assert!(claimed.lease_expires_at.is_some());
+        assert!(second.is_none(), "a leased job must not be claimed twice");
+    }
+
+    #[tokio::test]
+    async fn test_expired_lease_is_reclaimed_and_lost_by_the_old_worker() {
+        let _lock = DATABASE_LOCK.lock().await;
+        let Some(queue) = queue().await else {
+            return;
+        };
+        let job = enqueue_for_new_campaign(&queue, "Job Reclaim Test").await;
+
+        claim(&queue, "worker-a").await;
+        expire_lease(&queue, job.id).await;
+        let reclaimed = claim(&queue, "worker-b").await;
+        let old_lease = queue.renew_lease(job.id, "worker-a").await.unwrap();
+        let new_lease = queue.renew_lease(job.id, "worker-b").await.unwrap();
+        let old_finish = queue.finish(job.id, "worker-a", "failed", Some("stale worker")).await.unwrap();
+        let after_old_finish = reload(&queue, job.id).await;
+
+        delete_campaign(&queue, job.campaign_id).await;
+
+        assert_eq!(reclaimed.id, job.id);
+        assert_eq!(reclaimed.attempts, 2);
+        assert_eq!(reclaimed.worker_id.as_deref(), Some("worker-b"));
+        assert_eq!(old_lease, Lease::Lost);
+        assert_eq!(new_lease, Lease::Held);
+        assert!(!old_finish, "the old worker must not record an outcome");
+        assert_eq!(after_old_finish.state, "running");
+    }
+
+    #[tokio::test]
+    async fn test_job_cancelled_while_orphaned_finishes_cancelled() {
+        let _lock = DATABASE_LOCK.lock().await;
+        let Some(queue) = queue().await else {
+            return;
+        };
+        let job = enqueue_for_new_campaign(&queue, "Job Orphan Cancel Test").await;
+
+        claim(&queue, "worker-a").await;
+        // The worker holding the job is gone, so only the flag records the cancellation
+        queue.request_cancel(job.campaign_id).await.unwrap();
+        expire_lease(&queue, job.id).await;
+        let reclaimed = claim(&queue, "worker-b").await;
+        queue.process(reclaimed, "worker-b").await;
+        let finished = reload(&queue, job.id).await;
+        let campaign_status: String = sqlx::query_scalar("SELECT status FROM campaigns WHERE id = $1")
+            .bind(job.campaign_id)
+            .fetch_one(&queue.pool)
+            .await
+            .unwrap();
+
+        delete_campaign(&queue, job.campaign_id).await;
+
+        assert_eq!(finished.state, "cancelled");
+        assert!(finished.lease_expires_at.is_none());
+        assert_eq!(campaign_status, "cancelled");
+    }
+}
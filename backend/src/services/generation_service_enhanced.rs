@@ -1,114 +1,795 @@
 use crate::error::{ApiError, ApiResult};
-use crate::models::{Campaign, PhaseInfo, TOTAL_PHASES};
-use crate::services::{AnthropicClient, DatabaseServiceEnhanced, GraphQLClient, HasuraSchemaGenerator, Tool};
+use crate::models::{
+    Campaign, CascadeMode, NewGenerationCall, PhaseInfo, PhaseOutput, TokenBudget, PHASE_1A_CORE_WORLD, PHASE_1B_CHARACTER_BUILDING, PHASE_1C_SOCIAL_FRAMEWORK,
+    PHASE_2A_PC_ENTITIES, PHASE_2B_PC_LOCATIONS, PHASE_2C_PC_ITEMS, PHASE_3A_QUESTS_ENCOUNTERS,
+    PHASE_3B_WORLD_POPULATION, PHASE_3C_RELATIONSHIPS, TOTAL_PHASES,
+};
+use crate::services::{
+    estimate_tokens, prompt_variables, summarize_dependency_context, validate_against_schema, Content, DatabaseServiceEnhanced, GenerationEvent, GenerationEventSender,
+    GraphQLClient, HasuraSchemaGenerator, LlmProvider, Message, PromptRegistry, ReferenceResolver, RetryClock, RetryListener, RetryNotice,
+    StreamListener, StreamProgress, TableSummary, Tool, ToolCallResult, Usage, UsageMeter, RETRY_CLOCK, RETRY_LISTENER, STREAM_LISTENER, USAGE_METER,
+};
+use futures::stream::{FuturesUnordered, StreamExt};
+use serde::Serialize;
 use serde_json::{json, Value as JsonValue};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
 pub struct GenerationServiceEnhanced {
     database: Arc<DatabaseServiceEnhanced>,
     graphql: Arc<GraphQLClient>,
-    anthropic: Arc<AnthropicClient>,
+    llm: Arc<dyn LlmProvider>,
     schema_generator: Arc<RwLock<HasuraSchemaGenerator>>,
+    max_concurrency: usize,
+    events: GenerationEventSender,
+    cancellations: Mutex<HashMap<i32, Arc<CancellationToken>>>,
+    budgets: Mutex<HashMap<i32, TokenBudget>>,
+    /// Recorded tool inputs per phase for campaigns being replayed rather than generated
+    replays: Mutex<HashMap<i32, HashMap<String, JsonValue>>>,
+    max_repair_attempts: u32,
+    context_token_budget: u32,
+    prompts: Arc<PromptRegistry>,
 }
 
+/// Clears a run's cancellation token, budget and replay inputs when the run ends, including when
+/// its future is dropped because the worker lost the job's lease. Entries already replaced by a
+/// newer run of the same campaign are left alone.
+struct RunGuard<'a> {
+    service: &'a GenerationServiceEnhanced,
+    campaign_id: i32,
+    token: Arc<CancellationToken>,
+}
+
+impl Drop for RunGuard<'_> {
+    fn drop(&mut self) {
+        let mut cancellations = self.service.cancellations.lock().unwrap();
+        if cancellations.get(&self.campaign_id).is_some_and(|token| Arc::ptr_eq(token, &self.token)) {
+            cancellations.remove(&self.campaign_id);
+            self.service.budgets.lock().unwrap().remove(&self.campaign_id);
+            self.service.replays.lock().unwrap().remove(&self.campaign_id);
+        }
+    }
+}
+
+/// Sent as the system prompt with every phase request.
+const PHASE_SYSTEM_PROMPT: &str = "You are an expert D&D 5e campaign designer building a consistent, interconnected world. \
+    Always answer by calling the provided tool with a complete input that follows its schema exactly.";
+
+/// Minimum gap between `LlmProgress` events for one streamed call.
+const STREAM_PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Repair attempts per phase unless configured otherwise.
+const DEFAULT_MAX_REPAIR_ATTEMPTS: u32 = 2;
+
+/// Tokens of dependency context per phase prompt unless configured otherwise.
+const DEFAULT_CONTEXT_TOKEN_BUDGET: u32 = 2000;
+
 #[derive(Debug)]
 struct GenerationPhase {
     name: String,
     description: String,
+    number: i32,
+    dependencies: Vec<String>,
     max_tokens: u32,
     temperature: f32,
 }
 
+impl GenerationPhase {
+    fn from_phase_info(phase_info: &PhaseInfo) -> Self {
+        Self {
+            name: phase_info.name.clone(),
+            description: phase_info.description.clone(),
+            number: phase_info.number,
+            dependencies: phase_info.dependencies.clone(),
+            max_tokens: match phase_info.name.as_str() {
+                "phase_1a_core_world" => 8000,
+                "phase_1b_character_building" => 6000,
+                "phase_1c_social_framework" => 7000,
+                "phase_2a_pc_entities" => 8000,
+                "phase_2b_pc_locations" => 8000,
+                "phase_2c_pc_items" => 6000,
+                "phase_3a_quests_encounters" => 10000,
+                "phase_3b_world_population" => 12000,
+                "phase_3c_relationships" => 8000,
+                _ => 8000,
+            },
+            temperature: if phase_info.name.starts_with("phase_1") { 0.7 } 
+                        else if phase_info.name.starts_with("phase_2") { 0.8 }
+                        else { 0.9 },
+        }
+    }
+}
+
+/// Which phases a resumed generation will skip and which it will run.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResumePlan {
+    pub campaign_id: i32,
+    pub completed_phases: Vec<String>,
+    pub remaining_phases: Vec<String>,
+}
+
+/// Which phases a single-phase regeneration will re-run and which it will flag as stale.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegenerationPlan {
+    pub campaign_id: i32,
+    pub phase: String,
+    pub cascade: CascadeMode,
+    pub rerun_phases: Vec<String>,
+    pub stale_phases: Vec<String>,
+}
+
 impl GenerationServiceEnhanced {
     pub fn new(
         database: Arc<DatabaseServiceEnhanced>, 
         graphql: Arc<GraphQLClient>, 
-        anthropic: Arc<AnthropicClient>,
-        schema_generator: Arc<RwLock<HasuraSchemaGenerator>>
+        llm: Arc<dyn LlmProvider>,
+        schema_generator: Arc<RwLock<HasuraSchemaGenerator>>,
+        max_concurrency: usize,
+        events: GenerationEventSender,
     ) -> Self {
-        Self { database, graphql, anthropic, schema_generator }
+        Self { database, graphql, llm, schema_generator, max_concurrency: max_concurrency.max(1), events, cancellations: Mutex::new(HashMap::new()), budgets: Mutex::new(HashMap::new()), replays: Mutex::new(HashMap::new()), max_repair_attempts: DEFAULT_MAX_REPAIR_ATTEMPTS, context_token_budget: DEFAULT_CONTEXT_TOKEN_BUDGET, prompts: Arc::new(PromptRegistry::builtin()) }
+    }
+
+    /// How many times a phase whose output is rejected is sent back to the model for correction.
+    pub fn with_max_repair_attempts(mut self, max_repair_attempts: u32) -> Self {
+        self.max_repair_attempts = max_repair_attempts;
+        self
+    }
+
+    /// How many tokens of earlier phases' names and ids each phase prompt may include.
+    pub fn with_context_token_budget(mut self, context_token_budget: u32) -> Self {
+        self.context_token_budget = context_token_budget;
+        self
+    }
+
+    /// Use these prompt templates instead of the built-in ones.
+    pub fn with_prompts(mut self, prompts: Arc<PromptRegistry>) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Signal a generation running in this process to stop. Returns false if none is running here.
+    pub fn cancel_generation(&self, campaign_id: i32) -> bool {
+        match self.cancellations.lock().unwrap().get(&campaign_id) {
+            Some(token) => {
+                info!("Cancelling generation for campaign {}", campaign_id);
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn emit(&self, event: GenerationEvent) {
+        // Sending only fails when nobody is listening, which is fine
+        let _ = self.events.send(event);
+    }
+
+    /// Call the model with a phase's tool and report the tokens it used, plus any retries and,
+    /// for streaming providers, how much output has arrived so far. The call's estimated input
+    /// plus `max_tokens` is reserved from the campaign's budget first, so that repair calls are
+    /// checked as well.
+    async fn call_phase_tool(&self, campaign_id: i32, phase: &GenerationPhase, messages: Vec<Message>, tool: Tool) -> ApiResult<ToolCallResult> {
+        let events = self.events.clone();
+        let phase_name = phase.name.clone();
+        let on_retry: RetryListener = Arc::new(move |notice: RetryNotice| {
+            let _ = events.send(GenerationEvent::LlmRetry {
+                campaign_id,
+                phase: phase_name.clone(),
+                attempt: notice.attempt,
+                delay_ms: notice.delay.as_millis() as u64,
+                reason: notice.reason,
+            });
+        });
+
+        let events = self.events.clone();
+        let phase_name = phase.name.clone();
+        let last_progress = Mutex::new(Instant::now());
+        let on_progress: StreamListener = Arc::new(move |progress: StreamProgress| {
+            let mut last = last_progress.lock().unwrap();
+            if last.elapsed() < STREAM_PROGRESS_INTERVAL {
+                return;
+            }
+            *last = Instant::now();
+            let _ = events.send(GenerationEvent::LlmProgress {
+                campaign_id,
+                phase: phase_name.clone(),
+                bytes: progress.bytes,
+                // Roughly four bytes per token, as for the mock provider's estimates
+                estimated_tokens: progress.bytes.div_ceil(4),
+            });
+        });
+
+        let logged_messages = serde_json::to_value(&messages)?;
+        let estimate = [PHASE_SYSTEM_PROMPT.to_string(), logged_messages.to_string(), tool.input_schema.to_string()]
+            .iter()
+            .map(|text| u64::from(estimate_tokens(text)))
+            .sum::<u64>()
+            + u64::from(phase.max_tokens);
+        let reserved = self.reserve_call_budget(campaign_id, phase, estimate)?;
+
+        let tool_name = tool.name.clone();
+        let tool_schema_hash = schema_hash(&tool.input_schema);
+        let started = Instant::now();
+        let meter = Arc::new(UsageMeter::default());
+        let call = self.llm.generate_with_tool_messages(Some(PHASE_SYSTEM_PROMPT), messages, tool, phase.max_tokens, phase.temperature);
+        let outcome = USAGE_METER
+            .scope(meter.clone(), RETRY_LISTENER.scope(on_retry, STREAM_LISTENER.scope(on_progress, call)))
+            .await;
+
+        // A failed call is charged whatever the provider reported before it failed
+        let (raw_response, error_message, usage) = match &outcome {
+            Ok(result) => (Some(result.raw_response.clone()), None, result.usage),
+            Err(e) => (None, Some(e.to_string()), meter.reported()),
+        };
+        self.settle_call_budget(campaign_id, reserved, usage);
+        let logged = NewGenerationCall {
+            campaign_id,
+            phase_name: phase.name.clone(),
+            provider: self.llm.name().to_string(),
+            model: self.llm.model().to_string(),
+            system_prompt: Some(PHASE_SYSTEM_PROMPT.to_string()),
+            messages: logged_messages,
+            tool_name,
+            tool_schema_hash,
+            temperature: phase.temperature,
+            max_tokens: phase.max_tokens,
+            raw_response,
+            error_message,
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+        if let Err(e) = self.database.record_generation_call(&logged).await {
+            warn!("Failed to record generation call for campaign {} phase {}: {}", campaign_id, phase.name, e);
+        }
+
+        if outcome.is_ok() || usage.input_tokens > 0 || usage.output_tokens > 0 {
+            self.emit(GenerationEvent::TokenUsage {
+                campaign_id,
+                phase: phase.name.clone(),
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+            });
+
+            // Usage accounting must never fail a phase that already produced content
+            if let Err(e) = self
+                .database
+                .record_generation_usage(campaign_id, &phase.name, self.llm.name(), self.llm.model(), usage)
+                .await
+            {
+                warn!("Failed to record token usage for campaign {} phase {}: {}", campaign_id, phase.name, e);
+            }
+        }
+        outcome
+    }
+
+    /// Generate a phase's content with its tool and save it. Output that fails schema
+    /// validation, or that the GraphQL insert rejects, is answered with a tool result listing
+    /// the problems and the model is asked to call the tool again, up to `max_repair_attempts`
+    /// times. Only the latest rejected call is kept in the conversation. Each rejected attempt
+    /// is recorded. A campaign being replayed saves its recorded input instead of calling the model.
+    async fn generate_and_save_phase(&self, campaign_id: i32, phase: &GenerationPhase, prompt: &str, mut tool: Tool) -> ApiResult<()> {
+        let resolver = self.reference_resolver(campaign_id, phase).await?;
+        resolver.widen_tool_schema(&mut tool.input_schema);
+
+        let replayed = self
+            .replays
+            .lock()
+            .unwrap()
+            .get(&campaign_id)
+            .and_then(|inputs| inputs.get(&phase.name).cloned());
+        if let Some(input) = replayed {
+            info!("Replaying recorded {} output for campaign {}", phase.name, campaign_id);
+            return self.validate_and_save_phase(campaign_id, phase, &tool, &resolver, &input).await;
+        }
+
+        let mut messages = vec![Message::user(prompt)];
+        let mut attempt = 0;
+
+        loop {
+            let call = self.call_phase_tool(campaign_id, phase, messages, tool.clone()).await?;
+            let input = call.input;
+
+            let error = match self.validate_and_save_phase(campaign_id, phase, &tool, &resolver, &input).await {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt < self.max_repair_attempts && is_repairable(&e) => e,
+                Err(e) => return Err(e),
+            };
+            attempt += 1;
+            warn!("Phase {} output rejected, repair attempt {}/{}: {}", phase.name, attempt, self.max_repair_attempts, error);
+
+            self.database
+                .record_repair_attempt(campaign_id, &phase.name, attempt, &tool.name, &input, &error)
+                .await?;
+            self.emit(GenerationEvent::RepairAttempt {
+                campaign_id,
+                phase: phase.name.clone(),
+                attempt,
+                reason: error.to_string(),
+            });
+
+            // A failed save may have inserted some tables before the one that was rejected
+            if let Some(phase_info) = PhaseInfo::get_phase_info(&phase.name) {
+                self.database.clear_phase_data(campaign_id, &[phase_info]).await?;
+            }
+
+            messages = vec![
+                Message::user(prompt),
+                Message::assistant_blocks(vec![Content::ToolUse { id: call.id.clone(), name: tool.name.clone(), input }]),
+                Message::user_blocks(vec![
+                    Content::ToolResult { tool_use_id: call.id, content: repair_feedback(&error), is_error: true },
+                    Content::Text {
+                        text: format!("Call the {} tool again with a complete, corrected input that fixes every problem listed.", tool.name),
+                    },
+                ]),
+            ];
+        }
+    }
+
+    /// Resolve the names in a phase's tool input to ids of earlier phases' rows, check it
+    /// against the tool schema and save it, recording the input so the phase can be replayed
+    /// later. Unresolved references are reported alongside schema violations.
+    async fn validate_and_save_phase(
+        &self,
+        campaign_id: i32,
+        phase: &GenerationPhase,
+        tool: &Tool,
+        resolver: &ReferenceResolver,
+        input: &JsonValue,
+    ) -> ApiResult<()> {
+        let mut resolved = input.clone();
+        let mut violations = resolver.resolve(&mut resolved);
+        violations.extend(validate_against_schema(&tool.input_schema, &resolved));
+        if !violations.is_empty() {
+            for violation in &violations {
+                warn!("{} output invalid at {}: {}", tool.name, violation.path, violation.message);
+            }
+            return Err(ApiError::InvalidToolOutput { tool: tool.name.clone(), violations });
+        }
+
+        self.save_phase_content(campaign_id, &phase.name, &resolved).await?;
+
+        // The content is saved; losing the replay copy must not fail the phase. The input is
+        // recorded as given so a replay resolves its names against the new campaign's ids
+        if let Err(e) = self.database.record_phase_output(campaign_id, &phase.name, &tool.name, input).await {
+            warn!("Failed to record {} output for campaign {}: {}", phase.name, campaign_id, e);
+        }
+        Ok(())
+    }
+
+    async fn save_phase_content(&self, campaign_id: i32, phase_name: &str, content: &JsonValue) -> ApiResult<()> {
+        match phase_name {
+            PHASE_1A_CORE_WORLD => self.save_phase_1a_content(campaign_id, content).await,
+            PHASE_1B_CHARACTER_BUILDING => self.save_phase_1b_content(campaign_id, content).await,
+            PHASE_1C_SOCIAL_FRAMEWORK => self.save_phase_1c_content(campaign_id, content).await,
+            PHASE_2A_PC_ENTITIES => self.save_phase_2a_content(campaign_id, content).await,
+            PHASE_2B_PC_LOCATIONS => self.save_phase_2b_content(campaign_id, content).await,
+            PHASE_2C_PC_ITEMS => self.save_phase_2c_content(campaign_id, content).await,
+            PHASE_3A_QUESTS_ENCOUNTERS => self.save_phase_3a_content(campaign_id, content).await,
+            PHASE_3B_WORLD_POPULATION => self.save_phase_3b_content(campaign_id, content).await,
+            PHASE_3C_RELATIONSHIPS => self.save_phase_3c_content(campaign_id, content).await,
+            _ => Err(ApiError::BadRequest(format!("Unknown phase: {}", phase_name))),
+        }
     }
 
     pub async fn generate_campaign_content(&self, campaign_id: i32) -> ApiResult<()> {
         info!("Starting enhanced 9-phase content generation for campaign {}", campaign_id);
 
-        // Get phase configuration from the campaign model
-        let phase_infos = PhaseInfo::get_all_phases();
-        let phases: Vec<GenerationPhase> = phase_infos.iter().map(|phase_info| {
-            GenerationPhase {
-                name: phase_info.name.clone(),
-                description: phase_info.description.clone(),
-                max_tokens: match phase_info.name.as_str() {
-                    "phase_1a_core_world" => 8000,
-                    "phase_1b_character_building" => 6000,
-                    "phase_1c_social_framework" => 7000,
-                    "phase_2a_pc_entities" => 8000,
-                    "phase_2b_pc_locations" => 8000,
-                    "phase_2c_pc_items" => 6000,
-                    "phase_3a_quests_encounters" => 10000,
-                    "phase_3b_world_population" => 12000,
-                    "phase_3c_relationships" => 8000,
-                    _ => 8000,
-                },
-                temperature: if phase_info.name.starts_with("phase_1") { 0.7 } 
-                            else if phase_info.name.starts_with("phase_2") { 0.8 }
-                            else { 0.9 },
-            }
-        }).collect();
+        let phase_names: Vec<String> = PhaseInfo::get_all_phases().into_iter().map(|p| p.name).collect();
 
         // Initialize phase tracking
         self.database.initialize_generation_phases(campaign_id, TOTAL_PHASES).await?;
+        self.database.reset_phase_records(campaign_id, &phase_names).await?;
 
-        // Track completed phases for dependency validation
-        let mut completed_phases: Vec<String> = Vec::new();
+        self.run_phases(campaign_id, PhaseInfo::get_all_phases(), Vec::new()).await
+    }
 
-        // Execute each phase with dependency validation
-        for (phase_number, phase) in phases.iter().enumerate() {
-            info!("Starting Phase {}: {}", phase_number + 1, phase.name);
-            
-            // Validate dependencies before executing phase
-            if let Err(e) = PhaseInfo::validate_dependencies(&completed_phases, &phase.name) {
+    /// Work out which phases a failed generation still has to run, rejecting campaigns
+    /// that are finished or currently generating.
+    pub async fn plan_resume(&self, campaign_id: i32) -> ApiResult<ResumePlan> {
+        let campaign = self.database.get_campaign(campaign_id).await?;
+
+        match campaign.status.as_str() {
+            "generating" => return Err(ApiError::BadRequest("Campaign is already generating".to_string())),
+            "completed" => return Err(ApiError::BadRequest("Campaign generation already completed".to_string())),
+            _ => {}
+        }
+
+        let completed_phases = self.completed_phases(&campaign).await?;
+        let remaining_phases = PhaseInfo::remaining_phases(&completed_phases)
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+
+        Ok(ResumePlan { campaign_id, completed_phases, remaining_phases })
+    }
+
+    /// Resume a failed generation, running only the phases that have not completed.
+    pub async fn resume_campaign_generation(&self, campaign_id: i32) -> ApiResult<()> {
+        let plan = self.plan_resume(campaign_id).await?;
+        info!(
+            "Resuming generation for campaign {}: {} phases completed, {} remaining",
+            campaign_id, plan.completed_phases.len(), plan.remaining_phases.len()
+        );
+
+        // Throw away partial writes from the phases we are about to run again
+        let remaining = PhaseInfo::remaining_phases(&plan.completed_phases);
+        self.database.clear_phase_data(campaign_id, &remaining).await?;
+        self.database.reset_phase_records(campaign_id, &plan.remaining_phases).await?;
+
+        let progress = plan.completed_phases.len() as i32 * 100 / TOTAL_PHASES;
+        self.database.mark_generation_resumed(campaign_id, progress).await?;
+
+        self.run_phases(campaign_id, remaining, plan.completed_phases).await
+    }
+
+    /// Work out what regenerating `phase_name` will touch, rejecting unknown phases, campaigns that
+    /// are currently generating, and phases whose own dependencies are not complete.
+    pub async fn plan_regeneration(
+        &self,
+        campaign_id: i32,
+        phase_name: &str,
+        cascade: CascadeMode,
+    ) -> ApiResult<RegenerationPlan> {
+        if PhaseInfo::get_phase_info(phase_name).is_none() {
+            return Err(ApiError::BadRequest(format!("Unknown phase: {}", phase_name)));
+        }
+
+        let campaign = self.database.get_campaign(campaign_id).await?;
+        if campaign.status == "generating" {
+            return Err(ApiError::BadRequest("Campaign is already generating".to_string()));
+        }
+
+        let completed_phases = self.completed_phases(&campaign).await?;
+        PhaseInfo::validate_dependencies(&completed_phases, phase_name).map_err(ApiError::BadRequest)?;
+
+        let dependents: Vec<String> = PhaseInfo::dependents(phase_name).into_iter().map(|p| p.name).collect();
+        let (rerun_phases, stale_phases) = match cascade {
+            CascadeMode::Rerun => (std::iter::once(phase_name.to_string()).chain(dependents).collect(), Vec::new()),
+            CascadeMode::MarkStale => (vec![phase_name.to_string()], dependents),
+        };
+
+        Ok(RegenerationPlan {
+            campaign_id,
+            phase: phase_name.to_string(),
+            cascade,
+            rerun_phases,
+            stale_phases,
+        })
+    }
+
+    /// Throw away one phase's content and generate it again, then either re-run its dependents
+    /// or leave them in place marked as stale.
+    pub async fn regenerate_phase(&self, campaign_id: i32, phase_name: &str, cascade: CascadeMode) -> ApiResult<()> {
+        let plan = self.plan_regeneration(campaign_id, phase_name, cascade).await?;
+        info!(
+            "Regenerating {} for campaign {}: re-running {:?}, marking stale {:?}",
+            phase_name, campaign_id, plan.rerun_phases, plan.stale_phases
+        );
+
+        let rerun: Vec<PhaseInfo> = PhaseInfo::get_all_phases()
+            .into_iter()
+            .filter(|p| plan.rerun_phases.contains(&p.name))
+            .collect();
+
+        // Stale phases keep their rows; their references to the cleared rows are set to NULL
+        // (migration 0014) rather than cascading
+        self.database.clear_phase_data(campaign_id, &rerun).await?;
+        self.database.reset_phase_records(campaign_id, &plan.rerun_phases).await?;
+        self.database.mark_phases_stale(campaign_id, &plan.stale_phases).await?;
+
+        let completed_phases: Vec<String> = PhaseInfo::get_all_phases()
+            .into_iter()
+            .map(|p| p.name)
+            .filter(|name| !plan.rerun_phases.contains(name) && !plan.stale_phases.contains(name))
+            .collect();
+        let progress = completed_phases.len() as i32 * 100 / TOTAL_PHASES;
+        self.database.mark_generation_resumed(campaign_id, progress).await?;
+
+        self.run_phases(campaign_id, rerun, completed_phases).await
+    }
+
+    /// The recorded phase outputs a replay of `source_campaign_id` will save, rejecting sources
+    /// that are missing any phase.
+    pub async fn plan_replay(&self, source_campaign_id: i32) -> ApiResult<Vec<PhaseOutput>> {
+        let outputs = self.database.get_phase_outputs(source_campaign_id).await?;
+        let missing: Vec<String> = PhaseInfo::get_all_phases()
+            .into_iter()
+            .map(|p| p.name)
+            .filter(|name| !outputs.iter().any(|output| &output.phase_name == name))
+            .collect();
+
+        if !missing.is_empty() {
+            return Err(ApiError::BadRequest(format!(
+                "Campaign {} has no recorded output for: {}",
+                source_campaign_id,
+                missing.join(", ")
+            )));
+        }
+        Ok(outputs)
+    }
+
+    /// Fill a campaign with the tool inputs recorded for `source_campaign_id`, running every
+    /// phase's save path without calling the model.
+    pub async fn replay_campaign_generation(&self, campaign_id: i32, source_campaign_id: i32) -> ApiResult<()> {
+        let outputs = self.plan_replay(source_campaign_id).await?;
+        info!("Replaying generation of campaign {} into campaign {}", source_campaign_id, campaign_id);
+
+        let phases = PhaseInfo::get_all_phases();
+        let phase_names: Vec<String> = phases.iter().map(|p| p.name.clone()).collect();
+
+        // An interrupted replay starts over rather than resuming
+        self.database.clear_phase_data(campaign_id, &phases).await?;
+        self.database.initialize_generation_phases(campaign_id, TOTAL_PHASES).await?;
+        self.database.reset_phase_records(campaign_id, &phase_names).await?;
+
+        let inputs = outputs.into_iter().map(|output| (output.phase_name, output.tool_input)).collect();
+        self.replays.lock().unwrap().insert(campaign_id, inputs);
+        self.run_phases(campaign_id, phases, Vec::new()).await
+    }
+
+    /// Phases that have completed for a campaign, falling back to the campaign row for
+    /// campaigns generated before per-phase tracking existed.
+    async fn completed_phases(&self, campaign: &Campaign) -> ApiResult<Vec<String>> {
+        let completed_phases = self.database.get_completed_phases(campaign.id).await?;
+        if !completed_phases.is_empty() {
+            return Ok(completed_phases);
+        }
+
+        if campaign.status == "completed" {
+            return Ok(PhaseInfo::get_all_phases().into_iter().map(|p| p.name).collect());
+        }
+
+        // Failed legacy campaigns only recorded the last completed phase
+        match (campaign.current_phase_status.as_deref(), campaign.generation_phase.as_deref()) {
+            (Some("completed"), Some(last_phase)) => Ok(PhaseInfo::completed_through(last_phase)),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    async fn run_phases(&self, campaign_id: i32, phases: Vec<PhaseInfo>, completed_phases: Vec<String>) -> ApiResult<()> {
+        // Registered first so the guard clears this run's budget and replay inputs on every exit
+        let token = Arc::new(CancellationToken::new());
+        self.cancellations.lock().unwrap().insert(campaign_id, token.clone());
+        let _run = RunGuard { service: self, campaign_id, token: token.clone() };
+
+        let campaign = self.database.get_campaign(campaign_id).await?;
+        let replaying = self.replays.lock().unwrap().contains_key(&campaign_id);
+        if let Some(limit) = campaign.max_token_budget().filter(|_| !replaying) {
+            let spent = self.database.get_total_tokens(campaign_id).await?.max(0) as u64;
+            info!("Campaign {} has a token budget of {} ({} already spent)", campaign_id, limit, spent);
+            self.budgets.lock().unwrap().insert(campaign_id, TokenBudget::new(limit, spent));
+        }
+
+        self.run_phase_graph(campaign_id, phases, completed_phases, &token).await
+    }
+
+    /// Refuse to start a phase whose `max_tokens` no longer fits in the campaign's budget, if it
+    /// has one. Nothing is held back here; each call reserves what it needs when it is made.
+    fn check_budget(&self, campaign_id: i32, phase: &GenerationPhase) -> ApiResult<()> {
+        let budgets = self.budgets.lock().unwrap();
+        let Some(budget) = budgets.get(&campaign_id) else {
+            return Ok(());
+        };
+
+        let requested = u64::from(phase.max_tokens);
+        let remaining = budget.remaining();
+        if requested > remaining {
+            return Err(ApiError::TokenBudgetExceeded { phase: phase.name.clone(), requested, remaining });
+        }
+        Ok(())
+    }
+
+    /// Hold back `estimate` tokens for one model call, returning how many were reserved (none
+    /// when the campaign has no budget), or fail when the call could overspend.
+    fn reserve_call_budget(&self, campaign_id: i32, phase: &GenerationPhase, estimate: u64) -> ApiResult<u64> {
+        let mut budgets = self.budgets.lock().unwrap();
+        let Some(budget) = budgets.get_mut(&campaign_id) else {
+            return Ok(0);
+        };
+
+        budget.reserve(estimate).map_err(|remaining| ApiError::TokenBudgetExceeded {
+            phase: phase.name.clone(),
+            requested: estimate,
+            remaining,
+        })?;
+        Ok(estimate)
+    }
+
+    fn settle_call_budget(&self, campaign_id: i32, reserved: u64, usage: Usage) {
+        if let Some(budget) = self.budgets.lock().unwrap().get_mut(&campaign_id) {
+            budget.settle(reserved, u64::from(usage.input_tokens) + u64::from(usage.output_tokens));
+        }
+    }
+
+    /// Run `phases` as a DAG: each phase starts once its dependencies have completed, with up to
+    /// `max_concurrency` phases in flight. After a failure no new phases start, but phases already
+    /// running are allowed to finish so a later resume can skip them. Cancelling `token` aborts
+    /// running phases and rolls back whatever they had written.
+    async fn run_phase_graph(
+        &self,
+        campaign_id: i32,
+        phases: Vec<PhaseInfo>,
+        mut completed_phases: Vec<String>,
+        token: &CancellationToken,
+    ) -> ApiResult<()> {
+        let phases = PhaseInfo::topological_order(phases).map_err(ApiError::BadRequest)?;
+        let scheduled: Vec<String> = phases.iter().map(|p| p.name.clone()).collect();
+
+        // Every dependency must either be complete already or be produced by this run
+        let satisfiable: Vec<String> = completed_phases.iter().chain(scheduled.iter()).cloned().collect();
+        for phase in &phases {
+            if let Err(e) = PhaseInfo::validate_dependencies(&satisfiable, &phase.name) {
                 error!("Phase dependency validation failed: {}", e);
+                self.emit(GenerationEvent::Error { campaign_id, phase: Some(phase.name.clone()), message: e.clone() });
                 self.database.update_campaign_status_with_error(
                     campaign_id,
-                    &format!("Dependency validation failed for phase {}: {}", phase_number + 1, e),
+                    &format!("Dependency validation failed for phase {}: {}", phase.number, e),
                 ).await?;
                 return Err(ApiError::BadRequest(e));
             }
-            
-            match self.execute_phase(campaign_id, phase, phase_number as i32 + 1).await {
-                Ok(_) => {
-                    self.database.update_generation_phase(
+        }
+
+        let mut pending: Vec<GenerationPhase> = phases.iter().map(GenerationPhase::from_phase_info).collect();
+        let mut running = FuturesUnordered::new();
+        let mut failure: Option<(i32, ApiError)> = None;
+        let mut interrupted: Vec<String> = Vec::new();
+
+        loop {
+            if failure.is_none() && !token.is_cancelled() {
+                while running.len() < self.max_concurrency {
+                    let Some(index) = pending
+                        .iter()
+                        .position(|p| p.dependencies.iter().all(|d| completed_phases.contains(d)))
+                    else {
+                        break;
+                    };
+
+                    if let Err(e) = self.check_budget(campaign_id, &pending[index]) {
+                        warn!("Not starting Phase {}: {}", pending[index].number, e);
+                        self.emit(GenerationEvent::Error {
+                            campaign_id,
+                            phase: Some(pending[index].name.clone()),
+                            message: e.to_string(),
+                        });
+                        failure = Some((pending[index].number, e));
+                        break;
+                    }
+
+                    let phase = pending.remove(index);
+                    info!("Starting Phase {}: {}", phase.number, phase.name);
+                    if let Err(e) = self.database.mark_phase_running(campaign_id, &phase.name).await {
+                        error!("Could not start Phase {}: {} - Error: {}", phase.number, phase.name, e);
+                        failure = Some((phase.number, e));
+                        break;
+                    }
+                    self.emit(GenerationEvent::PhaseStarted {
                         campaign_id,
-                        &phase.name,
-                        ((phase_number + 1) * 100 / phases.len()) as i32,
-                        Some("completed"),
-                    ).await?;
+                        phase: phase.name.clone(),
+                        phase_number: phase.number,
+                    });
+
+                    let phase_token = token.clone();
+                    running.push(async move {
+                        // Every call the phase makes, repairs included, shares one retry budget
+                        let execute = RETRY_CLOCK.scope(Arc::new(RetryClock::default()), self.execute_phase(campaign_id, &phase, phase.number));
+                        let result = tokio::select! {
+                            result = execute => Some(result),
+                            _ = phase_token.cancelled() => None,
+                        };
+                        (phase, result)
+                    });
+                }
+            }
+
+            let Some((phase, result)) = running.next().await else {
+                break;
+            };
+
+            match result {
+                None => {
+                    warn!("Phase {}: {} interrupted by cancellation", phase.number, phase.name);
+                    interrupted.push(phase.name.clone());
+                }
+                Some(Ok(_)) => {
+                    let progress = (completed_phases.len() as i32 + 1) * 100 / TOTAL_PHASES;
+                    // A database error here must not drop the phases still running, so it is
+                    // recorded as the run's failure and the loop keeps draining
+                    if let Err(e) = self.record_phase_completed(campaign_id, &phase, progress).await {
+                        error!("Could not record Phase {}: {} as completed - Error: {}", phase.number, phase.name, e);
+                        self.emit(GenerationEvent::Error {
+                            campaign_id,
+                            phase: Some(phase.name.clone()),
+                            message: e.to_string(),
+                        });
+                        if failure.is_none() {
+                            failure = Some((phase.number, e));
+                        }
+                        continue;
+                    }
                     completed_phases.push(phase.name.clone());
-                    info!("Completed Phase {}: {}", phase_number + 1, phase.name);
+                    self.emit(GenerationEvent::PhaseCompleted {
+                        campaign_id,
+                        phase: phase.name.clone(),
+                        phase_number: phase.number,
+                        progress,
+                    });
+                    info!("Completed Phase {}: {}", phase.number, phase.name);
                 }
-                Err(e) => {
-                    error!("Failed Phase {}: {} - Error: {}", phase_number + 1, phase.name, e);
-                    self.database.update_campaign_status_with_error(
+                Some(Err(e)) => {
+                    error!("Failed Phase {}: {} - Error: {}", phase.number, phase.name, e);
+                    if let Err(db_error) = self.database.mark_phase_failed(campaign_id, &phase.name, &e.to_string()).await {
+                        error!("Could not record Phase {}: {} as failed - Error: {}", phase.number, phase.name, db_error);
+                    }
+                    self.emit(GenerationEvent::Error {
                         campaign_id,
-                        &format!("Failed in phase {}: {}", phase_number + 1, e),
-                    ).await?;
-                    return Err(e);
+                        phase: Some(phase.name.clone()),
+                        message: e.to_string(),
+                    });
+                    if failure.is_none() {
+                        failure = Some((phase.number, e));
+                    }
                 }
             }
         }
 
+        if token.is_cancelled() {
+            // Roll back partial writes so a later resume starts these phases cleanly
+            let rollback: Vec<PhaseInfo> = PhaseInfo::get_all_phases()
+                .into_iter()
+                .filter(|p| interrupted.contains(&p.name))
+                .collect();
+            self.database.clear_phase_data(campaign_id, &rollback).await?;
+            self.database.reset_phase_records(campaign_id, &interrupted).await?;
+            self.database.update_campaign_status_cancelled(campaign_id).await?;
+            self.emit(GenerationEvent::GenerationCancelled { campaign_id });
+            info!("Cancelled generation for campaign {} ({} phases rolled back)", campaign_id, interrupted.len());
+            return Err(ApiError::Cancelled);
+        }
+
+        if let Some((phase_number, e)) = failure {
+            self.database.update_campaign_status_with_error(
+                campaign_id,
+                &format!("Failed in phase {}: {}", phase_number, e),
+            ).await?;
+            return Err(e);
+        }
+
+        if !pending.is_empty() {
+            let names: Vec<&str> = pending.iter().map(|p| p.name.as_str()).collect();
+            let message = format!("Phases could not be scheduled: {}", names.join(", "));
+            self.emit(GenerationEvent::Error { campaign_id, phase: None, message: message.clone() });
+            self.database.update_campaign_status_with_error(campaign_id, &message).await?;
+            return Err(ApiError::BadRequest(message));
+        }
+
         // Mark campaign as ready
-        self.database.update_generation_phase(campaign_id, "completed", 100, Some("all_phases_complete")).await?;
+        let stale_phases = PhaseInfo::remaining_phases(&completed_phases);
+        if stale_phases.is_empty() {
+            self.database.update_generation_phase(campaign_id, "completed", 100, Some("all_phases_complete")).await?;
+            info!("Successfully completed all 9 phases for campaign {}", campaign_id);
+        } else {
+            let progress = completed_phases.len() as i32 * 100 / TOTAL_PHASES;
+            self.database.update_generation_phase(campaign_id, "completed", progress, Some("dependents_stale")).await?;
+            info!("Completed regeneration for campaign {} with {} stale phases", campaign_id, stale_phases.len());
+        }
         self.database.update_campaign_status_completed(campaign_id).await?;
-        info!("Successfully completed all 9 phases for campaign {}", campaign_id);
+        self.emit(GenerationEvent::GenerationCompleted { campaign_id });
 
         Ok(())
     }
 
+    async fn record_phase_completed(&self, campaign_id: i32, phase: &GenerationPhase, progress: i32) -> ApiResult<()> {
+        self.database.mark_phase_completed(campaign_id, &phase.name).await?;
+        self.database.update_generation_phase(campaign_id, &phase.name, progress, Some("completed")).await
+    }
+
     async fn execute_phase(&self, campaign_id: i32, phase: &GenerationPhase, phase_number: i32) -> ApiResult<()> {
         match phase.name.as_str() {
             "phase_1a_core_world" => self.execute_phase_1a_core_world(campaign_id, phase, phase_number).await,
@@ -124,54 +805,50 @@ impl GenerationServiceEnhanced {
         }
     }
 
+    /// The named rows saved by the phases `phase` depends on.
+    async fn dependency_summaries(&self, campaign_id: i32, phase: &GenerationPhase) -> ApiResult<Vec<TableSummary>> {
+        let tables: Vec<String> = PhaseInfo::ancestors(&phase.name).into_iter().flat_map(|p| p.tables).collect();
+        self.database.get_table_summaries(campaign_id, &tables).await
+    }
+
+    /// What the phase's dependencies created, by name and id, within the context token budget.
+    async fn dependency_context(&self, campaign_id: i32, phase: &GenerationPhase) -> ApiResult<String> {
+        let summaries = self.dependency_summaries(campaign_id, phase).await?;
+        Ok(summarize_dependency_context(&summaries, self.context_token_budget))
+    }
+
+    async fn reference_resolver(&self, campaign_id: i32, phase: &GenerationPhase) -> ApiResult<ReferenceResolver> {
+        let foreign_keys = self.database.get_foreign_keys().await?;
+        let summaries = self.dependency_summaries(campaign_id, phase).await?;
+        Ok(ReferenceResolver::new(foreign_keys, summaries))
+    }
+
+    fn render_phase_prompt(&self, phase: &GenerationPhase, campaign: &Campaign, context: &str) -> ApiResult<String> {
+        let rendered = self.prompts.render(&phase.name, &prompt_variables(campaign, context))?;
+        info!("Using prompt template {} {} for campaign {}", phase.name, rendered.version, campaign.id);
+        Ok(rendered.text)
+    }
+
     // Phase 1A: Core World Systems
     async fn execute_phase_1a_core_world(&self, campaign_id: i32, phase: &GenerationPhase, _phase_number: i32) -> ApiResult<()> {
         info!("Executing Phase 1A: Core World Systems for campaign {}", campaign_id);
 
         // Get campaign details for context
         let campaign = self.database.get_campaign(campaign_id).await?;
-        
-        // Build prompt with campaign context
-        let prompt = format!(
-            "You are creating core world systems for the D&D 5e campaign: '{}'\n\n\
-            Setting: {}\n\
-            Themes: {}\n\
-            Tone: {}\n\
-            Campaign Length: {}\n\n\
-            {}\n\n\
-            Generate comprehensive foundational world systems that are independent of player characters.\n\
-            Focus on creating: calendar systems, planes of existence, geography, historical periods, \
-            economic systems, legal frameworks, and celestial bodies.\n\n\
-            Use the provided tool to structure your response with all required data.",
-            campaign.name,
-            campaign.setting.as_deref().unwrap_or("Fantasy world"),
-            campaign.themes.join(", "),
-            campaign.tone,
-            campaign.campaign_length,
-            campaign.additional_notes.as_deref().unwrap_or("")
-        );
+
+        let prompt = self.render_phase_prompt(phase, &campaign, "")?;
 
         // Get phase-specific tool schema from Hasura
         let schema_gen = self.schema_generator.read().await;
         let tool = schema_gen.get_phase_1a_schemas()
             .ok_or_else(|| ApiError::BadRequest("Failed to generate Phase 1A schemas".to_string()))?;
 
-        // Call AI with tool to generate content
-        info!("Calling Anthropic API for Phase 1A generation...");
-        let response = match self.anthropic.generate_with_tool(&prompt, tool, phase.max_tokens, phase.temperature).await {
-            Ok(resp) => {
-                info!("Successfully received response from Anthropic API");
-                resp
-            },
-            Err(e) => {
-                error!("Failed to generate content with Anthropic API: {:?}", e);
-                return Err(e);
-            }
-        };
-        
-        // Save content using database service
-        info!("Saving Phase 1A content to database...");
-        self.save_phase_1a_content(campaign_id, &response).await?;
+        // Call AI with tool to generate content, then save it
+        info!("Generating Phase 1A content with {}...", self.llm.name());
+        if let Err(e) = self.generate_and_save_phase(campaign_id, phase, &prompt, tool).await {
+            error!("Failed to generate Phase 1A content: {:?}", e);
+            return Err(e);
+        }
 
         Ok(())
     }
@@ -181,32 +858,15 @@ impl GenerationServiceEnhanced {
         info!("Executing Phase 1B: Character Building Systems for campaign {}", campaign_id);
 
         let campaign = self.database.get_campaign(campaign_id).await?;
-        let phase_1a_context = self.database.get_phase_1a_context(campaign_id).await?;
-        
-        let prompt = format!(
-            "You are creating character building systems for the D&D 5e campaign: '{}'\n\n\
-            Setting: {}\n\
-            Themes: {}\n\
-            Tone: {}\n\n\
-            World Context from Phase 1A:\n\
-            {}\n\n\
-            Generate character creation systems including races, classes, feats, and backgrounds \
-            that fit the established world. Ensure racial origins tie to the geography and \
-            cultural elements align with the world's tone and themes.\n\n\
-            Use the provided tool to structure your response.",
-            campaign.name,
-            campaign.setting.as_deref().unwrap_or("Fantasy world"),
-            campaign.themes.join(", "),
-            campaign.tone,
-            serde_json::to_string_pretty(&phase_1a_context).unwrap_or_default()
-        );
+        let context = self.dependency_context(campaign_id, phase).await?;
+        
+        let prompt = self.render_phase_prompt(phase, &campaign, &context)?;
 
         let schema_gen = self.schema_generator.read().await;
         let tool = schema_gen.get_phase_1b_schemas()
             .ok_or_else(|| ApiError::BadRequest("Failed to generate Phase 1B schemas".to_string()))?;
 
-        let response = self.anthropic.generate_with_tool(&prompt, tool, phase.max_tokens, phase.temperature).await?;
-        self.save_phase_1b_content(campaign_id, &response).await?;
+        self.generate_and_save_phase(campaign_id, phase, &prompt, tool).await?;
 
         Ok(())
     }
@@ -216,32 +876,15 @@ impl GenerationServiceEnhanced {
         info!("Executing Phase 1C: Social Framework for campaign {}", campaign_id);
 
         let campaign = self.database.get_campaign(campaign_id).await?;
-        let phase_1_context = self.database.get_phase_1_context(campaign_id).await?;
-        
-        let prompt = format!(
-            "You are creating social framework for the D&D 5e campaign: '{}'\n\n\
-            Setting: {}\n\
-            Themes: {}\n\
-            Tone: {}\n\n\
-            Established World Systems:\n\
-            {}\n\n\
-            Generate social and religious systems including languages, cultures, factions, \
-            pantheons, and deities. Build on the established geography, races, and world history. \
-            Create interconnected social systems that reflect the campaign's themes.\n\n\
-            Use the provided tool to structure your response.",
-            campaign.name,
-            campaign.setting.as_deref().unwrap_or("Fantasy world"),
-            campaign.themes.join(", "),
-            campaign.tone,
-            serde_json::to_string_pretty(&phase_1_context).unwrap_or_default()
-        );
+        let context = self.dependency_context(campaign_id, phase).await?;
+        
+        let prompt = self.render_phase_prompt(phase, &campaign, &context)?;
 
         let schema_gen = self.schema_generator.read().await;
         let tool = schema_gen.get_phase_1c_schemas()
             .ok_or_else(|| ApiError::BadRequest("Failed to generate Phase 1C schemas".to_string()))?;
 
-        let response = self.anthropic.generate_with_tool(&prompt, tool, phase.max_tokens, phase.temperature).await?;
-        self.save_phase_1c_content(campaign_id, &response).await?;
+        self.generate_and_save_phase(campaign_id, phase, &prompt, tool).await?;
 
         Ok(())
     }
@@ -251,51 +894,15 @@ impl GenerationServiceEnhanced {
         info!("Executing Phase 2A: PC-Connected Entities for campaign {}", campaign_id);
 
         let campaign = self.database.get_campaign(campaign_id).await?;
-        let world_context = self.database.get_phase_1_context(campaign_id).await?;
+        let context = self.dependency_context(campaign_id, phase).await?;
         
-        let mut prompt = format!(
-            "You are creating PC-connected entities for the D&D 5e campaign: '{}'\n\n\
-            Setting: {}\n\
-            Themes: {}\n\
-            Tone: {}\n\n\
-            Player Characters:\n",
-            campaign.name,
-            campaign.setting.as_deref().unwrap_or("Fantasy world"),
-            campaign.themes.join(", "),
-            campaign.tone
-        );
-
-        // Add PC details
-        if let Some(pcs) = campaign.player_characters.as_array() {
-            for pc in pcs {
-                prompt.push_str(&format!(
-                    "- {} ({} {}, Level {}): {}\n",
-                    pc.get("name").and_then(|v| v.as_str()).unwrap_or("Unknown"),
-                    pc.get("race").and_then(|v| v.as_str()).unwrap_or("Unknown"),
-                    pc.get("class").and_then(|v| v.as_str()).unwrap_or("Unknown"),
-                    pc.get("level").and_then(|v| v.as_i64()).unwrap_or(1),
-                    pc.get("backstory").and_then(|v| v.as_str()).unwrap_or("No backstory")
-                ));
-            }
-        }
-
-        prompt.push_str(&format!(
-            "\n\nEstablished World Context:\n\
-            {}\n\n\
-            Generate NPCs and entities that have direct connections to the player character backstories. \
-            Each entity should have clear relationships to specific PCs based on their backgrounds, \
-            motivations, and story hooks. Focus on family members, mentors, rivals, allies, and \
-            significant figures from their past.\n\n\
-            Use the provided tool to structure your response.",
-            serde_json::to_string_pretty(&world_context).unwrap_or_default()
-        ));
+        let prompt = self.render_phase_prompt(phase, &campaign, &context)?;
 
         let schema_gen = self.schema_generator.read().await;
         let tool = schema_gen.get_phase_2a_schemas()
             .ok_or_else(|| ApiError::BadRequest("Failed to generate Phase 2A schemas".to_string()))?;
 
-        let response = self.anthropic.generate_with_tool(&prompt, tool, phase.max_tokens, phase.temperature).await?;
-        self.save_phase_2a_content(campaign_id, &response).await?;
+        self.generate_and_save_phase(campaign_id, phase, &prompt, tool).await?;
 
         Ok(())
     }
@@ -305,27 +912,15 @@ impl GenerationServiceEnhanced {
         info!("Executing Phase 2B: PC-Connected Locations for campaign {}", campaign_id);
 
         let campaign = self.database.get_campaign(campaign_id).await?;
-        let phase_context = self.database.get_phase_2_context(campaign_id).await?;
-        
-        let prompt = format!(
-            "You are creating PC-connected locations for the D&D 5e campaign: '{}'\n\n\
-            Established Context:\n\
-            {}\n\n\
-            Generate locations that are directly tied to PC backstories and the entities created in Phase 2A. \
-            Create hierarchical locations (cities → districts → buildings) that serve as homes, origins, \
-            training grounds, and significant places from PC histories. Each location should have clear \
-            connections to specific player characters.\n\n\
-            Use the provided tool to structure your response.",
-            campaign.name,
-            serde_json::to_string_pretty(&phase_context).unwrap_or_default()
-        );
+        let context = self.dependency_context(campaign_id, phase).await?;
+        
+        let prompt = self.render_phase_prompt(phase, &campaign, &context)?;
 
         let schema_gen = self.schema_generator.read().await;
         let tool = schema_gen.get_phase_2b_schemas()
             .ok_or_else(|| ApiError::BadRequest("Failed to generate Phase 2B schemas".to_string()))?;
 
-        let response = self.anthropic.generate_with_tool(&prompt, tool, phase.max_tokens, phase.temperature).await?;
-        self.save_phase_2b_content(campaign_id, &response).await?;
+        self.generate_and_save_phase(campaign_id, phase, &prompt, tool).await?;
 
         Ok(())
     }
@@ -335,27 +930,15 @@ impl GenerationServiceEnhanced {
         info!("Executing Phase 2C: PC-Connected Items for campaign {}", campaign_id);
 
         let campaign = self.database.get_campaign(campaign_id).await?;
-        let phase_context = self.database.get_phase_2_context(campaign_id).await?;
-        
-        let prompt = format!(
-            "You are creating PC-connected items for the D&D 5e campaign: '{}'\n\n\
-            Established Context:\n\
-            {}\n\n\
-            Generate equipment, artifacts, and magical items that are relevant to PC stories and \
-            connected to the entities and locations from previous phases. Create items with personal \
-            significance: family heirlooms, training weapons, artifacts from mentors, quest items, \
-            and tools that tie into PC backstories and future plot development.\n\n\
-            Use the provided tool to structure your response.",
-            campaign.name,
-            serde_json::to_string_pretty(&phase_context).unwrap_or_default()
-        );
+        let context = self.dependency_context(campaign_id, phase).await?;
+        
+        let prompt = self.render_phase_prompt(phase, &campaign, &context)?;
 
         let schema_gen = self.schema_generator.read().await;
         let tool = schema_gen.get_phase_2c_schemas()
             .ok_or_else(|| ApiError::BadRequest("Failed to generate Phase 2C schemas".to_string()))?;
 
-        let response = self.anthropic.generate_with_tool(&prompt, tool, phase.max_tokens, phase.temperature).await?;
-        self.save_phase_2c_content(campaign_id, &response).await?;
+        self.generate_and_save_phase(campaign_id, phase, &prompt, tool).await?;
 
         Ok(())
     }
@@ -365,27 +948,15 @@ impl GenerationServiceEnhanced {
         info!("Executing Phase 3A: Quest Hooks & Encounters for campaign {}", campaign_id);
 
         let campaign = self.database.get_campaign(campaign_id).await?;
-        let all_context = self.database.get_phase_3_context(campaign_id).await?;
-        
-        let prompt = format!(
-            "You are creating quest hooks and encounters for the D&D 5e campaign: '{}'\n\n\
-            Complete Context from Previous Phases:\n\
-            {}\n\n\
-            Generate adventure hooks, missions, and encounters that build on all the established content. \
-            Create quests that involve the PC-connected entities, utilize the established locations, \
-            and incorporate the world systems. Design encounters that challenge the party and advance \
-            the overall campaign narrative while respecting the tone and themes.\n\n\
-            Use the provided tool to structure your response.",
-            campaign.name,
-            serde_json::to_string_pretty(&all_context).unwrap_or_default()
-        );
+        let context = self.dependency_context(campaign_id, phase).await?;
+        
+        let prompt = self.render_phase_prompt(phase, &campaign, &context)?;
 
         let schema_gen = self.schema_generator.read().await;
         let tool = schema_gen.get_phase_3a_schemas()
             .ok_or_else(|| ApiError::BadRequest("Failed to generate Phase 3A schemas".to_string()))?;
 
-        let response = self.anthropic.generate_with_tool(&prompt, tool, phase.max_tokens, phase.temperature).await?;
-        self.save_phase_3a_content(campaign_id, &response).await?;
+        self.generate_and_save_phase(campaign_id, phase, &prompt, tool).await?;
 
         Ok(())
     }
@@ -395,27 +966,15 @@ impl GenerationServiceEnhanced {
         info!("Executing Phase 3B: World Population for campaign {}", campaign_id);
 
         let campaign = self.database.get_campaign(campaign_id).await?;
-        let all_context = self.database.get_phase_3_context(campaign_id).await?;
-        
-        let prompt = format!(
-            "You are populating the world for the D&D 5e campaign: '{}'\n\n\
-            Established Context:\n\
-            {}\n\n\
-            Generate additional world population to flesh out the established locations. Create shops, \
-            taverns, temples, and other businesses that make the world feel alive. Populate locations \
-            with additional NPCs, services, and points of interest that support the established narrative \
-            and provide resources for the party's adventures.\n\n\
-            Use the provided tool to structure your response.",
-            campaign.name,
-            serde_json::to_string_pretty(&all_context).unwrap_or_default()
-        );
+        let context = self.dependency_context(campaign_id, phase).await?;
+        
+        let prompt = self.render_phase_prompt(phase, &campaign, &context)?;
 
         let schema_gen = self.schema_generator.read().await;
         let tool = schema_gen.get_phase_3b_schemas()
             .ok_or_else(|| ApiError::BadRequest("Failed to generate Phase 3B schemas".to_string()))?;
 
-        let response = self.anthropic.generate_with_tool(&prompt, tool, phase.max_tokens, phase.temperature).await?;
-        self.save_phase_3b_content(campaign_id, &response).await?;
+        self.generate_and_save_phase(campaign_id, phase, &prompt, tool).await?;
 
         Ok(())
     }
@@ -425,28 +984,15 @@ impl GenerationServiceEnhanced {
         info!("Executing Phase 3C: Final Relationships for campaign {}", campaign_id);
 
         let campaign = self.database.get_campaign(campaign_id).await?;
-        let all_context = self.database.get_phase_3_context(campaign_id).await?;
-        
-        let prompt = format!(
-            "You are creating final relationships for the D&D 5e campaign: '{}'\n\n\
-            Complete Campaign Context:\n\
-            {}\n\n\
-            Generate the final relationship network that connects all entities, locations, factions, \
-            and items created in previous phases. Create entity-to-entity relationships, establish \
-            faction alliances and rivalries, connect entities to their home locations, assign item \
-            ownership, and create the social web that makes the campaign world feel interconnected \
-            and alive.\n\n\
-            Use the provided tool to structure your response.",
-            campaign.name,
-            serde_json::to_string_pretty(&all_context).unwrap_or_default()
-        );
+        let context = self.dependency_context(campaign_id, phase).await?;
+        
+        let prompt = self.render_phase_prompt(phase, &campaign, &context)?;
 
         let schema_gen = self.schema_generator.read().await;
         let tool = schema_gen.get_phase_3c_schemas()
             .ok_or_else(|| ApiError::BadRequest("Failed to generate Phase 3C schemas".to_string()))?;
 
-        let response = self.anthropic.generate_with_tool(&prompt, tool, phase.max_tokens, phase.temperature).await?;
-        self.save_phase_3c_content(campaign_id, &response).await?;
+        self.generate_and_save_phase(campaign_id, phase, &prompt, tool).await?;
 
         Ok(())
     }
@@ -462,7 +1008,7 @@ impl GenerationServiceEnhanced {
         let (prompt, tool) = self.build_pc_connected_prompt_with_tool(campaign_id, &world_data).await?;
         
         // Call AI with tool to generate PC-connected content
-        let response = self.anthropic.generate_with_tool(&prompt, tool, phase.max_tokens, phase.temperature).await?;
+        let response = self.call_phase_tool(campaign_id, phase, vec![Message::user(prompt.as_str())], tool).await?.input;
         
         // Save PC-connected content
         self.save_pc_connected_content(campaign_id, &response).await?;
@@ -482,7 +1028,7 @@ impl GenerationServiceEnhanced {
         let (prompt, tool) = self.build_world_population_prompt_with_tool(campaign_id, &world_data, &pc_data).await?;
         
         // Call AI with tool to generate world population content
-        let response = self.anthropic.generate_with_tool(&prompt, tool, phase.max_tokens, phase.temperature).await?;
+        let response = self.call_phase_tool(campaign_id, phase, vec![Message::user(prompt.as_str())], tool).await?.input;
         
         // Save world population content using GraphQL
         self.save_world_population_content_graphql(campaign_id, &response).await?;
@@ -495,7 +1041,7 @@ impl GenerationServiceEnhanced {
         info!("Calling AI with {} tokens max, temperature {}", max_tokens, temperature);
         
         // Call the actual Anthropic API
-        let response = self.anthropic.generate_content(prompt, max_tokens, temperature).await?;
+        let response = self.llm.generate_content(prompt, max_tokens, temperature).await?;
         
         // Parse the response as JSON
         match serde_json::from_str::<JsonValue>(&response) {
@@ -1331,7 +1877,6 @@ impl GenerationServiceEnhanced {
         // Process quest hooks
         if let Some(quest_hooks) = content.get("quest_hooks").and_then(|v| v.as_array()) {
             for quest in quest_hooks {
-                // Save quest (note: quest table doesn't exist, so this is a no-op with warning)
                 self.database.save_quest(&mut tx, campaign_id, quest, &entity_mapping, &location_mapping).await?;
             }
         }
@@ -1948,6 +2493,9 @@ impl GenerationServiceEnhanced {
         
         let saved_entities = self.graphql.save_phase_1a_data(campaign_id, content).await?;
         info!("Saved Phase 1A entities: {:?}", saved_entities);
+        for event in GenerationEvent::rows_saved(campaign_id, PHASE_1A_CORE_WORLD, &saved_entities) {
+            self.emit(event);
+        }
         
         Ok(())
     }
@@ -1957,6 +2505,9 @@ impl GenerationServiceEnhanced {
         
         let saved_entities = self.graphql.save_phase_1b_data(campaign_id, content).await?;
         info!("Saved Phase 1B entities: {:?}", saved_entities);
+        for event in GenerationEvent::rows_saved(campaign_id, PHASE_1B_CHARACTER_BUILDING, &saved_entities) {
+            self.emit(event);
+        }
         
         Ok(())
     }
@@ -1966,6 +2517,9 @@ impl GenerationServiceEnhanced {
         
         let saved_entities = self.graphql.save_phase_1c_data(campaign_id, content).await?;
         info!("Saved Phase 1C entities: {:?}", saved_entities);
+        for event in GenerationEvent::rows_saved(campaign_id, PHASE_1C_SOCIAL_FRAMEWORK, &saved_entities) {
+            self.emit(event);
+        }
         
         Ok(())
     }
@@ -1975,6 +2529,9 @@ impl GenerationServiceEnhanced {
         
         let saved_entities = self.graphql.save_phase_2a_data(campaign_id, content).await?;
         info!("Saved Phase 2A entities: {:?}", saved_entities);
+        for event in GenerationEvent::rows_saved(campaign_id, PHASE_2A_PC_ENTITIES, &saved_entities) {
+            self.emit(event);
+        }
         
         Ok(())
     }
@@ -1984,6 +2541,9 @@ impl GenerationServiceEnhanced {
         
         let saved_entities = self.graphql.save_phase_2b_data(campaign_id, content).await?;
         info!("Saved Phase 2B entities: {:?}", saved_entities);
+        for event in GenerationEvent::rows_saved(campaign_id, PHASE_2B_PC_LOCATIONS, &saved_entities) {
+            self.emit(event);
+        }
         
         Ok(())
     }
@@ -1993,6 +2553,9 @@ impl GenerationServiceEnhanced {
         
         let saved_entities = self.graphql.save_phase_2c_data(campaign_id, content).await?;
         info!("Saved Phase 2C entities: {:?}", saved_entities);
+        for event in GenerationEvent::rows_saved(campaign_id, PHASE_2C_PC_ITEMS, &saved_entities) {
+            self.emit(event);
+        }
         
         Ok(())
     }
@@ -2002,6 +2565,9 @@ impl GenerationServiceEnhanced {
         
         let saved_entities = self.graphql.save_phase_3a_data(campaign_id, content).await?;
         info!("Saved Phase 3A entities: {:?}", saved_entities);
+        for event in GenerationEvent::rows_saved(campaign_id, PHASE_3A_QUESTS_ENCOUNTERS, &saved_entities) {
+            self.emit(event);
+        }
         
         Ok(())
     }
@@ -2011,6 +2577,9 @@ impl GenerationServiceEnhanced {
         
         let saved_entities = self.graphql.save_phase_3b_data(campaign_id, content).await?;
         info!("Saved Phase 3B entities: {:?}", saved_entities);
+        for event in GenerationEvent::rows_saved(campaign_id, PHASE_3B_WORLD_POPULATION, &saved_entities) {
+            self.emit(event);
+        }
         
         Ok(())
     }
@@ -2020,6 +2589,9 @@ impl GenerationServiceEnhanced {
         
         let saved_entities = self.graphql.save_phase_3c_data(campaign_id, content).await?;
         info!("Saved Phase 3C entities: {:?}", saved_entities);
+        for event in GenerationEvent::rows_saved(campaign_id, PHASE_3C_RELATIONSHIPS, &saved_entities) {
+            self.emit(event);
+        }
         
         Ok(())
     }
@@ -2047,3 +2619,34 @@ impl GenerationServiceEnhanced {
         Ok(())
     }
 }
+
+/// Schema violations and inserts rejected for the values they carry can be fixed by the model.
+/// Other failures, such as a save path targeting a table or field that does not exist, cannot.
+fn is_repairable(error: &ApiError) -> bool {
+    match error {
+        ApiError::InvalidToolOutput { .. } => true,
+        ApiError::GraphQL { rejects_data, .. } => *rejects_data,
+        // Postgres classes 22 (data exception) and 23 (integrity constraint violation)
+        ApiError::Database(sqlx::Error::Database(e)) => {
+            e.code().is_some_and(|code| code.starts_with("22") || code.starts_with("23"))
+        }
+        _ => false,
+    }
+}
+
+/// Tool result content explaining why a tool input was rejected.
+fn repair_feedback(error: &ApiError) -> String {
+    match error {
+        ApiError::InvalidToolOutput { violations, .. } => {
+            let problems: Vec<String> = violations.iter().map(|v| format!("- {}: {}", v.path, v.message)).collect();
+            format!("The input does not match the tool schema or refers to records that do not exist:\n{}", problems.join("\n"))
+        }
+        other => format!("Saving the input failed: {}", other),
+    }
+}
+
+/// Hex SHA-256 of a tool's input schema, identifying which schema version a call was made with.
+fn schema_hash(schema: &JsonValue) -> String {
+    let digest = Sha256::digest(schema.to_string().as_bytes());
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
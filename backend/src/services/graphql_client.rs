@@ -29,6 +29,17 @@ pub struct GraphQLResponse {
 pub struct GraphQLError {
     pub message: String,
     pub path: Option<Vec<Value>>,
+    pub extensions: Option<Value>,
+}
+
+impl GraphQLError {
+    /// Whether Hasura rejected the values sent rather than the query itself: a constraint
+    /// violation, a data exception, or an error located in the mutation's variables.
+    pub fn rejects_data(&self) -> bool {
+        let extension = |key: &str| self.extensions.as_ref().and_then(|e| e.get(key)).and_then(Value::as_str);
+        matches!(extension("code"), Some("constraint-violation" | "data-exception"))
+            || extension("path").is_some_and(|path| path.starts_with("$.variableValues"))
+    }
 }
 
 impl GraphQLClient {
@@ -80,7 +91,10 @@ impl GraphQLClient {
         if let Some(errors) = graphql_response.errors {
             let error_messages: Vec<String> = errors.iter().map(|e| e.message.clone()).collect();
             error!("GraphQL errors: {:?}", error_messages);
-            return Err(ApiError::BadRequest(format!("GraphQL errors: {}", error_messages.join(", "))));
+            return Err(ApiError::GraphQL {
+                message: error_messages.join(", "),
+                rejects_data: errors.iter().all(GraphQLError::rejects_data),
+            });
         }
 
         graphql_response.data
@@ -934,4 +948,33 @@ impl CampaignGraphQLClient {
     pub async fn update_by_pk(&self, table: &str, pk_columns: Value, set_data: Value) -> ApiResult<Value> {
         self.client.update_by_pk(table, pk_columns, set_data).await
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(value: Value) -> GraphQLError {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_only_errors_about_the_values_sent_reject_data() {
+        let missing_table = error(json!({
+            "message": "field 'insert_npcs_one' not found in type: 'mutation_root'",
+            "extensions": {"path": "$.selectionSet.insert_npcs_one", "code": "validation-failed"}
+        }));
+        let foreign_key = error(json!({
+            "message": "Foreign key violation.",
+            "extensions": {"path": "$.selectionSet.insert_entities_one.args.object", "code": "constraint-violation"}
+        }));
+        let bad_value = error(json!({
+            "message": "expected a 32-bit integer for type \"Int\", but found a string",
+            "extensions": {"path": "$.variableValues.object.race_id", "code": "parse-failed"}
+        }));
+
+        assert!(!missing_table.rejects_data());
+        assert!(foreign_key.rejects_data());
+        assert!(bad_value.rejects_data());
+        assert!(!error(json!({"message": "unexpected"})).rejects_data());
+    }
+}
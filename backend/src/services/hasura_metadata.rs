@@ -0,0 +1,396 @@
+use crate::error::{ApiError, ApiResult};
+use crate::services::SqlSchema;
+use serde_json::{json, Value as JsonValue};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+/// Generation bookkeeping the backend keeps to itself; every other table is tracked.
+const UNTRACKED_TABLES: &[&str] = &[
+    "_sqlx_migrations",
+    "campaign_generation_phases",
+    "generation_calls",
+    "generation_jobs",
+    "generation_phase_outputs",
+    "generation_repair_attempts",
+    "generation_usage",
+];
+
+/// Columns the database fills in, so clients never insert them.
+const GENERATED_COLUMNS: &[&str] = &["id", "created_at", "updated_at"];
+
+/// Columns only the GM may see.
+const SECRET_COLUMNS: &[&str] = &["secrets", "secret_info", "pc_connection_description"];
+
+/// The session variable Hasura fills with the signed-in user's id.
+const USER_ID: &str = "X-Hasura-User-Id";
+
+/// The `campaigns` columns clients may update; the rest belong to the generation pipeline.
+const CAMPAIGN_UPDATE_COLUMNS: &[&str] = &[
+    "status",
+    "generation_phase",
+    "phase_progress",
+    "total_phases",
+    "current_phase_status",
+    "error_message",
+    "metadata",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HasuraRelationship {
+    pub name: String,
+    pub column: String,
+    /// The referenced table of an object relationship, the referencing table of an array one
+    pub table: String,
+}
+
+/// A tracked table and the relationships derived from the foreign keys into and out of it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HasuraTable {
+    pub name: String,
+    pub columns: BTreeSet<String>,
+    pub object_relationships: Vec<HasuraRelationship>,
+    pub array_relationships: Vec<HasuraRelationship>,
+}
+
+/// Every tracked table of `schema`, in name order.
+///
+/// A foreign key `t.x_id -> r` becomes the object relationship `x` on `t` and the array
+/// relationship `t` on `r`, or `t_by_x` when `t` has several keys into `r` or `t` is `r`.
+/// A name that clashes with a column or an earlier relationship gets a `_rel` suffix.
+pub fn hasura_tables(schema: &SqlSchema) -> Vec<HasuraTable> {
+    let mut tables: BTreeMap<String, HasuraTable> = schema
+        .tables()
+        .iter()
+        .filter(|(name, _)| !UNTRACKED_TABLES.contains(&name.as_str()))
+        .map(|(name, columns)| {
+            let table = HasuraTable {
+                name: name.clone(),
+                columns: columns.clone(),
+                object_relationships: Vec::new(),
+                array_relationships: Vec::new(),
+            };
+            (name.clone(), table)
+        })
+        .collect();
+
+    let foreign_keys: Vec<_> = schema
+        .foreign_keys()
+        .into_iter()
+        .filter(|key| tables.contains_key(&key.table_name) && tables.contains_key(&key.referenced_table))
+        .collect();
+    let mut keys_between: HashMap<(&str, &str), usize> = HashMap::new();
+    for key in &foreign_keys {
+        *keys_between.entry((&key.table_name, &key.referenced_table)).or_default() += 1;
+    }
+
+    for key in &foreign_keys {
+        let base = key.column_name.strip_suffix("_id").unwrap_or(&key.column_name);
+
+        let table = tables.get_mut(&key.table_name).unwrap();
+        let name = unique_name(table, base.to_string());
+        table.object_relationships.push(HasuraRelationship {
+            name,
+            column: key.column_name.clone(),
+            table: key.referenced_table.clone(),
+        });
+
+        let name = if key.table_name != key.referenced_table
+            && keys_between[&(key.table_name.as_str(), key.referenced_table.as_str())] == 1
+        {
+            key.table_name.clone()
+        } else {
+            format!("{}_by_{}", key.table_name, base)
+        };
+        let referenced = tables.get_mut(&key.referenced_table).unwrap();
+        let name = unique_name(referenced, name);
+        referenced.array_relationships.push(HasuraRelationship {
+            name,
+            column: key.column_name.clone(),
+            table: key.table_name.clone(),
+        });
+    }
+
+    tables.into_values().collect()
+}
+
+fn unique_name(table: &HasuraTable, name: String) -> String {
+    let taken = |name: &str| {
+        table.columns.contains(name)
+            || table.object_relationships.iter().chain(&table.array_relationships).any(|r| r.name == name)
+    };
+    let mut name = name;
+    while taken(&name) {
+        name.push_str("_rel");
+    }
+    name
+}
+
+/// The contents of `tables.yaml` and one `public_<table>.yaml` per tracked table, by file name.
+pub fn hasura_metadata_files(schema: &SqlSchema) -> BTreeMap<String, String> {
+    let tables = hasura_tables(schema);
+    let members = tables
+        .iter()
+        .find(|table| table.name == "campaigns")
+        .and_then(|campaigns| {
+            campaigns
+                .array_relationships
+                .iter()
+                .find(|rel| rel.table == "campaign_members" && rel.column == "campaign_id")
+        })
+        .map(|rel| rel.name.as_str());
+
+    let mut files: BTreeMap<String, String> = tables
+        .iter()
+        .map(|table| (format!("public_{}.yaml", table.name), table_yaml(table, members)))
+        .collect();
+    let includes: String = tables
+        .iter()
+        .map(|table| format!("- \"!include public_{}.yaml\"\n", table.name))
+        .collect();
+    files.insert("tables.yaml".to_string(), includes);
+    files
+}
+
+fn table_yaml(table: &HasuraTable, members: Option<&str>) -> String {
+    let mut yaml = format!("table:\n  name: {}\n  schema: public", table.name);
+
+    if !table.object_relationships.is_empty() {
+        yaml.push_str("\nobject_relationships:");
+        for rel in &table.object_relationships {
+            yaml.push_str(&format!("\n  - name: {}\n    using:\n      foreign_key_constraint_on: {}", rel.name, rel.column));
+        }
+    }
+
+    if !table.array_relationships.is_empty() {
+        yaml.push_str("\narray_relationships:");
+        for rel in &table.array_relationships {
+            yaml.push_str(&format!(
+                "\n  - name: {}\n    using:\n      foreign_key_constraint_on:\n        column: {}\n        table:\n          name: {}\n          schema: public",
+                rel.name, rel.column, rel.table
+            ));
+        }
+    }
+
+    if let Some(permissions) = members.and_then(|members| permissions(table, members)) {
+        for (kind, roles) in [
+            ("select_permissions", permissions.select),
+            ("insert_permissions", permissions.insert),
+            ("update_permissions", permissions.update),
+        ] {
+            yaml.push_str(&format!("\n{}:", kind));
+            for (role, permission) in roles {
+                yaml.push_str(&format!("\n  - role: {}\n    permission:{}", role, yaml_value(&permission, 6)));
+            }
+        }
+    }
+
+    yaml
+}
+
+struct TablePermissions {
+    select: Vec<(&'static str, JsonValue)>,
+    insert: Vec<(&'static str, JsonValue)>,
+    update: Vec<(&'static str, JsonValue)>,
+}
+
+/// `user` reads and writes the campaigns it runs as GM; `player` reads the campaigns it is a
+/// member of, minus [`SECRET_COLUMNS`]. Rows are matched through `members`, the `campaigns`
+/// array relationship to `campaign_members`. Tables without a `campaign_id` stay admin-only.
+fn permissions(table: &HasuraTable, members: &str) -> Option<TablePermissions> {
+    let member = json!({ "user_id": { "_eq": USER_ID } });
+    let gm = json!({ "user_id": { "_eq": USER_ID }, "role": { "_eq": "gm" } });
+
+    let player_columns: Vec<&str> = table
+        .columns
+        .iter()
+        .map(String::as_str)
+        .filter(|column| !SECRET_COLUMNS.contains(column))
+        .collect();
+    let player_columns = if player_columns.len() == table.columns.len() { json!("*") } else { json!(player_columns) };
+
+    if table.name == "campaigns" {
+        // The creator is matched directly as well: their GM membership is added by a trigger,
+        // which the insert's returning clause cannot see yet
+        let runs = json!({ "_or": [{ "created_by": { "_eq": USER_ID } }, { members: gm }] });
+        let insertable: Vec<&str> = table
+            .columns
+            .iter()
+            .map(String::as_str)
+            .filter(|column| !GENERATED_COLUMNS.contains(column) && *column != "created_by")
+            .collect();
+        return Some(TablePermissions {
+            select: vec![
+                ("player", json!({ "columns": player_columns, "filter": { members: member } })),
+                ("user", json!({ "columns": "*", "filter": runs })),
+            ],
+            insert: vec![(
+                "user",
+                json!({ "check": {}, "columns": insertable, "set": { "created_by": USER_ID } }),
+            )],
+            update: vec![("user", json!({ "columns": CAMPAIGN_UPDATE_COLUMNS, "filter": runs, "check": runs }))],
+        });
+    }
+
+    let campaign = table.object_relationships.iter().find(|rel| rel.column == "campaign_id")?;
+    let runs = json!({ campaign.name.as_str(): { members: gm } });
+    Some(TablePermissions {
+        select: vec![
+            ("player", json!({ "columns": player_columns, "filter": { campaign.name.as_str(): { members: member } } })),
+            ("user", json!({ "columns": "*", "filter": runs })),
+        ],
+        insert: vec![("user", json!({ "check": runs, "columns": "*" }))],
+        update: vec![("user", json!({ "columns": "*", "filter": runs, "check": runs }))],
+    })
+}
+
+/// Block-style YAML for the objects, string lists and scalars permissions are made of.
+fn yaml_value(value: &JsonValue, indent: usize) -> String {
+    match value {
+        JsonValue::Object(map) if map.is_empty() => " {}".to_string(),
+        JsonValue::Object(map) => map
+            .iter()
+            .map(|(key, value)| format!("\n{:indent$}{}:{}", "", key, yaml_value(value, indent + 2), indent = indent))
+            .collect(),
+        JsonValue::Array(items) if items.iter().all(|item| !item.is_object()) => items
+            .iter()
+            .map(|item| format!("\n{:indent$}-{}", "", yaml_value(item, indent + 2), indent = indent))
+            .collect(),
+        JsonValue::Array(items) => items
+            .iter()
+            .map(|item| {
+                let entry = yaml_value(item, indent + 2);
+                // The first key goes on the dash line
+                format!("\n{:indent$}- {}", "", entry.trim_start(), indent = indent)
+            })
+            .collect(),
+        JsonValue::String(text) if text == "*" => " '*'".to_string(),
+        other => format!(" {}", other.as_str().map(str::to_string).unwrap_or_else(|| other.to_string())),
+    }
+}
+
+/// Problems with the committed metadata in `dir` compared to `files`: missing, outdated or
+/// no longer generated table files. Empty when the metadata is current.
+pub fn stale_hasura_metadata(dir: impl AsRef<Path>, files: &BTreeMap<String, String>) -> ApiResult<Vec<String>> {
+    let dir = dir.as_ref();
+    let mut problems = Vec::new();
+    for (name, contents) in files {
+        match std::fs::read_to_string(dir.join(name)) {
+            Ok(existing) if existing == *contents => {}
+            Ok(_) => problems.push(format!("{} is out of date", name)),
+            Err(_) => problems.push(format!("{} is missing", name)),
+        }
+    }
+    for name in table_files(dir)? {
+        if !files.contains_key(&name) {
+            problems.push(format!("{} is for a table the schema does not define", name));
+        }
+    }
+    Ok(problems)
+}
+
+/// Write `files` into `dir`, removing table files for tables that no longer exist.
+pub fn write_hasura_metadata(dir: impl AsRef<Path>, files: &BTreeMap<String, String>) -> ApiResult<()> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir).map_err(|e| write_error(dir, e))?;
+    for name in table_files(dir)? {
+        if !files.contains_key(&name) {
+            let path = dir.join(&name);
+            std::fs::remove_file(&path).map_err(|e| write_error(&path, e))?;
+        }
+    }
+    for (name, contents) in files {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).map_err(|e| write_error(&path, e))?;
+    }
+    Ok(())
+}
+
+/// The generated files already in `dir`.
+fn table_files(dir: &Path) -> ApiResult<Vec<String>> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| ApiError::Internal(anyhow::anyhow!("Failed to read {}: {}", dir.display(), e)))?;
+    Ok(entries
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| name == "tables.yaml" || (name.starts_with("public_") && name.ends_with(".yaml")))
+        .collect())
+}
+
+fn write_error(path: &Path, e: std::io::Error) -> ApiError {
+    ApiError::Internal(anyhow::anyhow!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(sql: &str) -> SqlSchema {
+        let mut schema = SqlSchema::default();
+        schema.apply_migration(sql);
+        schema
+    }
+
+    #[test]
+    fn test_relationships_are_named_after_foreign_keys() {
+        let schema = schema(
+            r#"
+            CREATE TABLE campaigns (id SERIAL PRIMARY KEY, name TEXT);
+            CREATE TABLE locations (
+                id SERIAL PRIMARY KEY,
+                campaign_id INTEGER REFERENCES campaigns(id),
+                parent_location_id INTEGER REFERENCES locations(id)
+            );
+            CREATE TABLE routes (
+                id SERIAL PRIMARY KEY,
+                from_location_id INTEGER REFERENCES locations(id),
+                to_location_id INTEGER REFERENCES locations(id),
+                campaign INTEGER REFERENCES campaigns(id)
+            );
+            CREATE TABLE generation_jobs (id SERIAL PRIMARY KEY, campaign_id INTEGER REFERENCES campaigns(id));
+            "#,
+        );
+        let tables = hasura_tables(&schema);
+        let names = |table: &str, array: bool| -> Vec<String> {
+            let table = tables.iter().find(|t| t.name == table).unwrap();
+            let relationships = if array { &table.array_relationships } else { &table.object_relationships };
+            relationships.iter().map(|r| r.name.clone()).collect()
+        };
+
+        assert_eq!(tables.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), ["campaigns", "locations", "routes"]);
+        assert_eq!(names("campaigns", true), ["locations", "routes"]);
+        assert_eq!(names("locations", false), ["campaign", "parent_location"]);
+        assert_eq!(names("locations", true), ["locations_by_parent_location", "routes_by_from_location", "routes_by_to_location"]);
+        assert_eq!(names("routes", false), ["campaign_rel", "from_location", "to_location"]);
+    }
+
+    #[test]
+    fn test_players_only_see_their_campaigns_without_gm_secrets() {
+        let schema = schema(
+            r#"
+            CREATE TABLE campaigns (id SERIAL PRIMARY KEY, name TEXT, created_by TEXT);
+            CREATE TABLE campaign_members (id SERIAL PRIMARY KEY, campaign_id INTEGER REFERENCES campaigns(id), user_id TEXT, role TEXT);
+            CREATE TABLE entities (id SERIAL PRIMARY KEY, campaign_id INTEGER REFERENCES campaigns(id), name TEXT, secrets TEXT[]);
+            CREATE TABLE lookup (id SERIAL PRIMARY KEY, name TEXT);
+            "#,
+        );
+        let files = hasura_metadata_files(&schema);
+        let entities = &files["public_entities.yaml"];
+
+        let player = entities.split("  - role: user").next().unwrap();
+        assert!(player.contains("- role: player\n    permission:\n      columns:\n        - campaign_id\n        - id\n        - name\n      filter:"));
+        assert!(player.contains("filter:\n        campaign:\n          campaign_members:\n            user_id:\n              _eq: X-Hasura-User-Id"));
+        assert!(!entities.contains("role: public"));
+        assert!(!entities.contains("role: player\n    permission:\n      check"), "players are read-only");
+        assert!(files["public_campaigns.yaml"].contains("      set:\n        created_by: X-Hasura-User-Id"));
+        assert!(!files["public_lookup.yaml"].contains("permissions"), "tables outside any campaign stay admin-only");
+    }
+
+    #[test]
+    fn test_committed_metadata_matches_the_migrations() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let schema = SqlSchema::from_migrations(root.join("migrations")).unwrap();
+        let files = hasura_metadata_files(&schema);
+
+        let stale = stale_hasura_metadata(root.join("../hasura/metadata/databases/default/tables"), &files).unwrap();
+        assert!(stale.is_empty(), "Hasura metadata is stale, run `just hasura-metadata`:\n{}", stale.join("\n"));
+    }
+}
@@ -0,0 +1,66 @@
+use crate::error::ApiResult;
+use crate::services::{Message, Tool, ToolCallResult, Usage};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+/// Rough token count for text, at about four bytes per token.
+pub fn estimate_tokens(text: &str) -> u32 {
+    (text.len() as u32).div_ceil(4)
+}
+
+/// Tokens providers reported for the current call, across all of its attempts. A call that
+/// fails has still been billed for whatever the provider processed before it failed.
+#[derive(Debug, Default)]
+pub struct UsageMeter {
+    reported: Mutex<Usage>,
+}
+
+impl UsageMeter {
+    pub fn reported(&self) -> Usage {
+        *self.reported.lock().unwrap()
+    }
+
+    pub fn add(&self, usage: Usage) {
+        let mut reported = self.reported.lock().unwrap();
+        reported.input_tokens = reported.input_tokens.saturating_add(usage.input_tokens);
+        reported.output_tokens = reported.output_tokens.saturating_add(usage.output_tokens);
+    }
+}
+
+tokio::task_local! {
+    /// Set around an LLM call by a caller that needs its usage even when the call fails.
+    pub static USAGE_METER: Arc<UsageMeter>;
+}
+
+/// Report usage a provider was billed for, successful or not, to the current call's meter.
+pub fn report_usage(usage: Usage) {
+    let _ = USAGE_METER.try_with(|meter| meter.add(usage));
+}
+
+/// A language model backend the generation pipeline can call.
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// Short identifier used in logs and configuration, e.g. `anthropic` or `mock`.
+    fn name(&self) -> &str;
+
+    /// Model identifier that usage is recorded and priced under.
+    fn model(&self) -> &str;
+
+    async fn generate_content(&self, prompt: &str, max_tokens: u32, temperature: f32) -> ApiResult<String>;
+
+    /// Force the model to call `tool` and return the tool input it produced.
+    async fn generate_with_tool(&self, prompt: &str, tool: Tool, max_tokens: u32, temperature: f32) -> ApiResult<ToolCallResult> {
+        self.generate_with_tool_messages(None, vec![Message::user(prompt)], tool, max_tokens, temperature).await
+    }
+
+    /// Like `generate_with_tool`, but with a system prompt and a prior conversation, e.g. an
+    /// earlier tool call followed by a tool result explaining what was wrong with it.
+    async fn generate_with_tool_messages(
+        &self,
+        system: Option<&str>,
+        messages: Vec<Message>,
+        tool: Tool,
+        max_tokens: u32,
+        temperature: f32,
+    ) -> ApiResult<ToolCallResult>;
+}
@@ -1,15 +1,41 @@
 pub mod anthropic;
+pub mod anthropic_stream;
 pub mod campaign_service;
 pub mod database_enhanced;
+pub mod generation_events;
+pub mod generation_jobs;
 pub mod generation_service_enhanced;
 pub mod graphql_client;
+pub mod hasura_metadata;
 pub mod hasura_schema_generator;
+pub mod llm_provider;
+pub mod mock_llm;
+pub mod openai;
+pub mod phase_context;
+pub mod prompt_templates;
 pub mod reference_data;
+pub mod reference_resolver;
+pub mod retry;
+pub mod schema_drift;
+pub mod schema_validator;
 
 pub use anthropic::*;
+pub use anthropic_stream::*;
 pub use campaign_service::*;
 pub use database_enhanced::*;
+pub use generation_events::*;
+pub use generation_jobs::*;
 pub use generation_service_enhanced::*;
 pub use graphql_client::*;
+pub use hasura_metadata::*;
 pub use hasura_schema_generator::*;
-pub use reference_data::*;
\ No newline at end of file
+pub use llm_provider::*;
+pub use mock_llm::*;
+pub use openai::*;
+pub use phase_context::*;
+pub use prompt_templates::*;
+pub use reference_data::*;
+pub use reference_resolver::*;
+pub use retry::*;
+pub use schema_drift::*;
+pub use schema_validator::*;
\ No newline at end of file
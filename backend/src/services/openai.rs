@@ -0,0 +1,379 @@
+use crate::error::{ApiError, ApiResult};
+use crate::services::{report_usage, Content, LlmProvider, Message, MessageContent, Tool, ToolCallResult, ToolChoice, Usage};
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{debug, error, info};
+
+/// Client for any server speaking the OpenAI chat completions API (OpenAI itself, vLLM,
+/// llama.cpp's server, Ollama, ...). Tools are sent as OpenAI function definitions.
+#[derive(Debug, Clone)]
+pub struct OpenAiClient {
+    client: Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChatCompletionRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub max_tokens: u32,
+    pub temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<FunctionTool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    /// Set on `tool` messages answering an earlier tool call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl ChatMessage {
+    pub fn text(role: &str, content: impl Into<String>) -> Self {
+        Self { role: role.to_string(), content: Some(content.into()), tool_calls: Vec::new(), tool_call_id: None }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FunctionTool {
+    #[serde(rename = "type")]
+    pub tool_type: String,
+    pub function: FunctionDefinition,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FunctionDefinition {
+    pub name: String,
+    pub description: String,
+    pub parameters: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ToolCall {
+    #[serde(default)]
+    pub id: String,
+    #[serde(rename = "type", default = "function_type")]
+    pub call_type: String,
+    pub function: FunctionCall,
+}
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FunctionCall {
+    pub name: String,
+    /// A JSON-encoded string per the OpenAI spec, though some local servers send an object.
+    pub arguments: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChatCompletionResponse {
+    pub choices: Vec<Choice>,
+    pub usage: Option<CompletionUsage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Choice {
+    pub message: ChatMessage,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CompletionUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+}
+
+impl From<&Tool> for FunctionTool {
+    fn from(tool: &Tool) -> Self {
+        FunctionTool {
+            tool_type: "function".to_string(),
+            function: FunctionDefinition {
+                name: tool.name.clone(),
+                description: tool.description.clone(),
+                parameters: tool.input_schema.clone(),
+            },
+        }
+    }
+}
+
+/// Map an Anthropic-style tool choice onto the OpenAI `tool_choice` field.
+pub fn openai_tool_choice(choice: &ToolChoice) -> Value {
+    match (choice.choice_type.as_str(), &choice.name) {
+        ("tool", Some(name)) => json!({"type": "function", "function": {"name": name}}),
+        ("any", _) => json!("required"),
+        ("none", _) => json!("none"),
+        _ => json!("auto"),
+    }
+}
+
+impl OpenAiClient {
+    pub fn new(base_url: String, api_key: Option<String>, model: String) -> Self {
+        let client = Client::builder()
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.filter(|key| !key.is_empty()),
+            model,
+        }
+    }
+
+    async fn send_request(&self, request: ChatCompletionRequest) -> ApiResult<ChatCompletionResponse> {
+        let url = format!("{}/chat/completions", self.base_url);
+        info!("Sending request to OpenAI-compatible API at {} (model {})", url, request.model);
+
+        let mut builder = self.client.post(&url).json(&request);
+        if let Some(api_key) = &self.api_key {
+            builder = builder.bearer_auth(api_key);
+        }
+
+        let response = builder.send().await.map_err(|e| {
+            error!("Network error calling {}: {}", url, e);
+            ApiError::Internal(anyhow::anyhow!("Network error: {}", e))
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            error!("OpenAI-compatible API returned {}: {}", status, error_text);
+            return match status {
+                StatusCode::UNAUTHORIZED => Err(ApiError::BadRequest("Invalid API key".to_string())),
+                StatusCode::TOO_MANY_REQUESTS => {
+                    Err(ApiError::BadRequest("Rate limit exceeded. Please try again later.".to_string()))
+                }
+                StatusCode::BAD_REQUEST => Err(ApiError::BadRequest(format!("Invalid request: {}", error_text))),
+                _ => Err(ApiError::Internal(anyhow::anyhow!("AI service error ({}): {}", status, error_text))),
+            };
+        }
+
+        let completion = response.json::<ChatCompletionResponse>().await.map_err(|e| {
+            error!("Failed to parse chat completion response: {}", e);
+            ApiError::Internal(anyhow::anyhow!("Failed to parse response: {}", e))
+        })?;
+
+        if let Some(usage) = &completion.usage {
+            debug!("Token usage - Input: {}, Output: {}", usage.prompt_tokens, usage.completion_tokens);
+            report_usage(Usage { input_tokens: usage.prompt_tokens, output_tokens: usage.completion_tokens });
+        }
+
+        Ok(completion)
+    }
+}
+
+/// Translate an Anthropic-style conversation into chat messages: tool uses become assistant
+/// `tool_calls` and tool results become `tool` messages.
+pub fn chat_messages(system: Option<&str>, messages: &[Message]) -> Vec<ChatMessage> {
+    let mut chat = Vec::new();
+    if let Some(system) = system {
+        chat.push(ChatMessage::text("system", system));
+    }
+
+    for message in messages {
+        let blocks = match &message.content {
+            MessageContent::Text(text) => {
+                chat.push(ChatMessage::text(&message.role, text.as_str()));
+                continue;
+            }
+            MessageContent::Blocks(blocks) => blocks,
+        };
+
+        let mut text = Vec::new();
+        let mut tool_calls = Vec::new();
+        for block in blocks {
+            match block {
+                Content::Text { text: t } => text.push(t.as_str()),
+                Content::ToolUse { id, name, input } => tool_calls.push(ToolCall {
+                    id: id.clone(),
+                    call_type: function_type(),
+                    function: FunctionCall { name: name.clone(), arguments: Value::String(input.to_string()) },
+                }),
+                Content::ToolResult { tool_use_id, content, .. } => chat.push(ChatMessage {
+                    role: "tool".to_string(),
+                    content: Some(content.clone()),
+                    tool_calls: Vec::new(),
+                    tool_call_id: Some(tool_use_id.clone()),
+                }),
+            }
+        }
+
+        if !text.is_empty() || !tool_calls.is_empty() {
+            chat.push(ChatMessage {
+                role: message.role.clone(),
+                content: (!text.is_empty()).then(|| text.join("\n\n")),
+                tool_calls,
+                tool_call_id: None,
+            });
+        }
+    }
+
+    chat
+}
+
+/// Pull the named tool's arguments out of a chat completion.
+fn extract_tool_call(completion: ChatCompletionResponse, tool_name: &str) -> ApiResult<ToolCallResult> {
+    let raw_response = serde_json::to_value(&completion)?;
+    let usage = completion
+        .usage
+        .map(|u| Usage { input_tokens: u.prompt_tokens, output_tokens: u.completion_tokens })
+        .unwrap_or_default();
+
+    let call = completion
+        .choices
+        .into_iter()
+        .flat_map(|choice| choice.message.tool_calls)
+        .find(|call| call.function.name == tool_name)
+        .ok_or_else(|| ApiError::BadRequest("No tool use found in response".to_string()))?;
+
+    let input = match call.function.arguments {
+        Value::String(arguments) => serde_json::from_str(&arguments)
+            .map_err(|e| ApiError::BadRequest(format!("Tool arguments are not valid JSON: {}", e)))?,
+        arguments => arguments,
+    };
+
+    Ok(ToolCallResult { id: call.id, input, usage, raw_response })
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiClient {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn generate_content(&self, prompt: &str, max_tokens: u32, temperature: f32) -> ApiResult<String> {
+        let request = ChatCompletionRequest {
+            model: self.model.clone(),
+            messages: vec![ChatMessage::text("user", prompt)],
+            max_tokens,
+            temperature,
+            tools: None,
+            tool_choice: None,
+        };
+
+        let completion = self.send_request(request).await?;
+        completion
+            .choices
+            .into_iter()
+            .find_map(|choice| choice.message.content)
+            .ok_or_else(|| ApiError::BadRequest("Empty response from AI".to_string()))
+    }
+
+    async fn generate_with_tool_messages(
+        &self,
+        system: Option<&str>,
+        messages: Vec<Message>,
+        tool: Tool,
+        max_tokens: u32,
+        temperature: f32,
+    ) -> ApiResult<ToolCallResult> {
+        info!("Generating content with tool: {}", tool.name);
+
+        let tool_choice = ToolChoice {
+            choice_type: "tool".to_string(),
+            name: Some(tool.name.clone()),
+        };
+        let request = ChatCompletionRequest {
+            model: self.model.clone(),
+            messages: chat_messages(system, &messages),
+            max_tokens,
+            temperature,
+            tools: Some(vec![FunctionTool::from(&tool)]),
+            tool_choice: Some(openai_tool_choice(&tool_choice)),
+        };
+
+        let completion = self.send_request(request).await?;
+        extract_tool_call(completion, &tool.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completion(arguments: Value) -> ChatCompletionResponse {
+        serde_json::from_value(json!({
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {"name": "generate_phase_1a", "arguments": arguments}
+                    }]
+                }
+            }],
+            "usage": {"prompt_tokens": 120, "completion_tokens": 45, "total_tokens": 165}
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tool_choice_mapping() {
+        let forced = ToolChoice { choice_type: "tool".to_string(), name: Some("generate_phase_1a".to_string()) };
+        assert_eq!(
+            openai_tool_choice(&forced),
+            json!({"type": "function", "function": {"name": "generate_phase_1a"}})
+        );
+
+        let any = ToolChoice { choice_type: "any".to_string(), name: None };
+        assert_eq!(openai_tool_choice(&any), json!("required"));
+    }
+
+    #[test]
+    fn test_extract_tool_call_parses_string_and_object_arguments() {
+        let result = extract_tool_call(completion(json!("{\"planes\": []}")), "generate_phase_1a").unwrap();
+        assert_eq!(result.id, "call_1");
+        assert_eq!(result.input, json!({"planes": []}));
+        assert_eq!(result.usage.input_tokens, 120);
+        assert_eq!(result.usage.output_tokens, 45);
+
+        let result = extract_tool_call(completion(json!({"planes": []})), "generate_phase_1a").unwrap();
+        assert_eq!(result.input, json!({"planes": []}));
+
+        assert!(extract_tool_call(completion(json!("{}")), "generate_phase_1b").is_err());
+    }
+
+    #[test]
+    fn test_chat_messages_translate_tool_turns() {
+        let messages = vec![
+            Message::user("Generate the world."),
+            Message::assistant_blocks(vec![Content::ToolUse {
+                id: "call_1".to_string(),
+                name: "generate_phase_1a".to_string(),
+                input: json!({"planes": []}),
+            }]),
+            Message::user_blocks(vec![
+                Content::ToolResult { tool_use_id: "call_1".to_string(), content: "planes is empty".to_string(), is_error: true },
+                Content::Text { text: "Try again.".to_string() },
+            ]),
+        ];
+
+        let chat = chat_messages(Some("Be precise."), &messages);
+        let roles: Vec<&str> = chat.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, vec!["system", "user", "assistant", "tool", "user"]);
+        assert_eq!(chat[2].tool_calls[0].id, "call_1");
+        assert_eq!(chat[2].tool_calls[0].function.arguments, json!("{\"planes\":[]}"));
+        assert_eq!(chat[3].tool_call_id.as_deref(), Some("call_1"));
+        assert_eq!(chat[4].content.as_deref(), Some("Try again."));
+    }
+}
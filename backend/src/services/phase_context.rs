@@ -0,0 +1,90 @@
+use crate::services::estimate_tokens;
+
+/// The named rows one table holds for a campaign, as `(id, name)` pairs in id order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableSummary {
+    pub table: String,
+    pub entries: Vec<(i32, String)>,
+}
+
+/// A compact listing of what earlier phases created, one line per table, for a later phase's
+/// prompt. When the full listing would exceed `max_tokens`, every table is cut to the same
+/// number of entries (the largest that fits) and the rest are counted instead of listed.
+pub fn summarize_dependency_context(tables: &[TableSummary], max_tokens: u32) -> String {
+    let longest = tables.iter().map(|t| t.entries.len()).max().unwrap_or(0);
+    if longest == 0 {
+        return String::new();
+    }
+
+    let (mut low, mut high) = (0, longest);
+    while low < high {
+        let mid = (low + high).div_ceil(2);
+        if estimate_tokens(&render(tables, mid)) <= max_tokens {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    render(tables, low)
+}
+
+fn render(tables: &[TableSummary], per_table: usize) -> String {
+    let mut lines = vec![
+        "Already created for this campaign (name and #id). Refer to these by name instead of inventing new ones:"
+            .to_string(),
+    ];
+
+    for summary in tables.iter().filter(|t| !t.entries.is_empty()) {
+        let mut listed: Vec<String> = summary
+            .entries
+            .iter()
+            .take(per_table)
+            .map(|(id, name)| format!("{} (#{})", name, id))
+            .collect();
+        let hidden = summary.entries.len().saturating_sub(per_table);
+        if hidden > 0 {
+            listed.push(format!("{} more", hidden));
+        }
+        lines.push(format!("- {}: {}", summary.table, listed.join(", ")));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(name: &str, count: i32) -> TableSummary {
+        TableSummary {
+            table: name.to_string(),
+            entries: (1..=count).map(|id| (id, format!("{} {}", name, id))).collect(),
+        }
+    }
+
+    #[test]
+    fn test_lists_everything_when_it_fits() {
+        let summary = summarize_dependency_context(&[table("races", 2), table("factions", 0), table("deities", 1)], 500);
+        let lines: Vec<&str> = summary.lines().skip(1).collect();
+
+        assert_eq!(lines, vec!["- races: races 1 (#1), races 2 (#2)", "- deities: deities 1 (#1)"]);
+    }
+
+    #[test]
+    fn test_trims_every_table_evenly_to_the_budget() {
+        let tables = [table("entities", 200), table("locations", 3)];
+        let summary = summarize_dependency_context(&tables, 150);
+
+        assert!(estimate_tokens(&summary) <= 150);
+        assert!(summary.contains("- locations: locations 1 (#1)"));
+        let entities = summary.lines().find(|line| line.starts_with("- entities")).unwrap();
+        assert!(entities.ends_with(" more"));
+        assert!(entities.contains("entities 1 (#1)"));
+    }
+
+    #[test]
+    fn test_no_prior_content_is_empty() {
+        assert_eq!(summarize_dependency_context(&[], 500), "");
+        assert_eq!(summarize_dependency_context(&[table("races", 0)], 500), "");
+    }
+}
@@ -0,0 +1,252 @@
+use crate::error::{ApiError, ApiResult};
+use crate::models::{
+    Campaign, PHASE_1A_CORE_WORLD, PHASE_1B_CHARACTER_BUILDING, PHASE_1C_SOCIAL_FRAMEWORK, PHASE_2A_PC_ENTITIES,
+    PHASE_2B_PC_LOCATIONS, PHASE_2C_PC_ITEMS, PHASE_3A_QUESTS_ENCOUNTERS, PHASE_3B_WORLD_POPULATION,
+    PHASE_3C_RELATIONSHIPS,
+};
+use minijinja::{Environment, UndefinedBehavior};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::info;
+
+/// Templates compiled into the binary, used when no template directory is available.
+const BUILTIN_TEMPLATES: [(&str, &str); 9] = [
+    (PHASE_1A_CORE_WORLD, include_str!("../../prompts/phase_1a_core_world/v1.jinja")),
+    (PHASE_1B_CHARACTER_BUILDING, include_str!("../../prompts/phase_1b_character_building/v1.jinja")),
+    (PHASE_1C_SOCIAL_FRAMEWORK, include_str!("../../prompts/phase_1c_social_framework/v1.jinja")),
+    (PHASE_2A_PC_ENTITIES, include_str!("../../prompts/phase_2a_pc_entities/v1.jinja")),
+    (PHASE_2B_PC_LOCATIONS, include_str!("../../prompts/phase_2b_pc_locations/v1.jinja")),
+    (PHASE_2C_PC_ITEMS, include_str!("../../prompts/phase_2c_pc_items/v1.jinja")),
+    (PHASE_3A_QUESTS_ENCOUNTERS, include_str!("../../prompts/phase_3a_quests_encounters/v1.jinja")),
+    (PHASE_3B_WORLD_POPULATION, include_str!("../../prompts/phase_3b_world_population/v1.jinja")),
+    (PHASE_3C_RELATIONSHIPS, include_str!("../../prompts/phase_3c_relationships/v1.jinja")),
+];
+
+/// A prompt rendered for one phase, tagged with the template version that produced it.
+#[derive(Debug, Clone)]
+pub struct RenderedPrompt {
+    pub version: String,
+    pub text: String,
+}
+
+/// Versioned phase prompt templates. Each phase uses its highest version (`v2` beats `v1`)
+/// unless a specific version has been selected for it.
+#[derive(Debug, Clone, Default)]
+pub struct PromptRegistry {
+    templates: HashMap<String, HashMap<String, String>>,
+    selected: HashMap<String, String>,
+}
+
+impl PromptRegistry {
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        for (phase, source) in BUILTIN_TEMPLATES {
+            registry.insert(phase, "v1", source);
+        }
+        registry
+    }
+
+    /// The built-in templates, overlaid with `<dir>/<phase>/<version>.jinja` files and with
+    /// versions pinned by a `phase=version,...` list. A missing directory is not an error.
+    pub fn load(dir: impl AsRef<Path>, selections: Option<&str>) -> ApiResult<Self> {
+        let mut registry = Self::builtin();
+        let dir = dir.as_ref();
+        if dir.is_dir() {
+            registry.load_dir(dir)?;
+        } else {
+            info!("Prompt template dir {} not found, using built-in templates", dir.display());
+        }
+        if let Some(selections) = selections {
+            registry.select_versions(selections)?;
+        }
+        Ok(registry)
+    }
+
+    pub fn insert(&mut self, phase: &str, version: &str, source: &str) {
+        self.templates
+            .entry(phase.to_string())
+            .or_default()
+            .insert(version.to_string(), source.to_string());
+    }
+
+    fn load_dir(&mut self, dir: &Path) -> ApiResult<()> {
+        let read_error = |path: &Path, e: std::io::Error| {
+            ApiError::Internal(anyhow::anyhow!("Failed to read prompt templates in {}: {}", path.display(), e))
+        };
+
+        let mut count = 0;
+        for phase_dir in std::fs::read_dir(dir).map_err(|e| read_error(dir, e))? {
+            let phase_dir = phase_dir.map_err(|e| read_error(dir, e))?.path();
+            let Some(phase) = phase_dir.file_name().and_then(|name| name.to_str()).map(str::to_string) else {
+                continue;
+            };
+            if !phase_dir.is_dir() {
+                continue;
+            }
+
+            for file in std::fs::read_dir(&phase_dir).map_err(|e| read_error(&phase_dir, e))? {
+                let path = file.map_err(|e| read_error(&phase_dir, e))?.path();
+                if path.extension().and_then(|ext| ext.to_str()) != Some("jinja") {
+                    continue;
+                }
+                let Some(version) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                    continue;
+                };
+                let source = std::fs::read_to_string(&path).map_err(|e| read_error(&path, e))?;
+                self.insert(&phase, version, &source);
+                count += 1;
+            }
+        }
+
+        info!("Loaded {} prompt templates from {}", count, dir.display());
+        Ok(())
+    }
+
+    /// Pin template versions from a `phase=version,phase=version` list.
+    pub fn select_versions(&mut self, selections: &str) -> ApiResult<()> {
+        for selection in selections.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (phase, version) = selection
+                .split_once('=')
+                .ok_or_else(|| ApiError::BadRequest(format!("Invalid prompt version selection: {}", selection)))?;
+            let (phase, version) = (phase.trim(), version.trim());
+
+            if !self.templates.get(phase).is_some_and(|versions| versions.contains_key(version)) {
+                return Err(ApiError::BadRequest(format!("No prompt template {} for {}", version, phase)));
+            }
+            self.selected.insert(phase.to_string(), version.to_string());
+        }
+        Ok(())
+    }
+
+    /// The version a phase will render with.
+    pub fn active_version(&self, phase: &str) -> Option<&str> {
+        if let Some(version) = self.selected.get(phase) {
+            return Some(version);
+        }
+        self.templates
+            .get(phase)?
+            .keys()
+            .max_by_key(|version| (version_number(version), version.as_str()))
+            .map(String::as_str)
+    }
+
+    pub fn render(&self, phase: &str, variables: &Value) -> ApiResult<RenderedPrompt> {
+        let version = self
+            .active_version(phase)
+            .ok_or_else(|| ApiError::BadRequest(format!("No prompt template for phase {}", phase)))?;
+        let source = &self.templates[phase][version];
+
+        let mut env = Environment::new();
+        env.set_undefined_behavior(UndefinedBehavior::Strict);
+        let text = env.render_str(source, variables).map_err(|e| {
+            ApiError::Internal(anyhow::anyhow!("Failed to render prompt {} {}: {}", phase, version, e))
+        })?;
+
+        Ok(RenderedPrompt { version: version.to_string(), text })
+    }
+}
+
+/// `v12` -> 12, so versions sort numerically; anything else sorts first.
+fn version_number(version: &str) -> u32 {
+    version.strip_prefix('v').and_then(|n| n.parse().ok()).unwrap_or(0)
+}
+
+/// Template variables for a campaign, with `context` summarizing earlier phases' output.
+pub fn prompt_variables(campaign: &Campaign, context: &str) -> Value {
+    let player_characters: Vec<Value> = campaign
+        .player_characters
+        .as_array()
+        .map(|pcs| {
+            pcs.iter()
+                .map(|pc| {
+                    json!({
+                        "name": pc.get("name").and_then(|v| v.as_str()).unwrap_or("Unknown"),
+                        "race": pc.get("race").and_then(|v| v.as_str()).unwrap_or("Unknown"),
+                        "class": pc.get("class").and_then(|v| v.as_str()).unwrap_or("Unknown"),
+                        "level": pc.get("level").and_then(|v| v.as_i64()).unwrap_or(1),
+                        "backstory": pc.get("backstory").and_then(|v| v.as_str()).unwrap_or("No backstory"),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    json!({
+        "campaign": {
+            "name": campaign.name,
+            "setting": campaign.setting.as_deref().unwrap_or("Fantasy world"),
+            "themes": campaign.themes.join(", "),
+            "tone": campaign.tone,
+            "difficulty": campaign.difficulty,
+            "campaign_length": campaign.campaign_length,
+            "progression_type": campaign.progression_type,
+            "starting_level": campaign.starting_level,
+            "additional_notes": campaign.additional_notes.as_deref().unwrap_or(""),
+        },
+        "player_characters": player_characters,
+        "context": context,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PhaseInfo;
+
+    fn variables() -> Value {
+        json!({
+            "campaign": {
+                "name": "The Lost Crown",
+                "setting": "Medieval fantasy kingdom",
+                "themes": "political intrigue, war",
+                "tone": "dark",
+                "difficulty": "hard",
+                "campaign_length": "long",
+                "progression_type": "milestone",
+                "starting_level": 3,
+                "additional_notes": "",
+            },
+            "player_characters": [
+                {"name": "Aria", "race": "Elf", "class": "Ranger", "level": 3, "backstory": "Exiled scout"}
+            ],
+            "context": "{\"planes\": []}",
+        })
+    }
+
+    #[test]
+    fn test_builtin_templates_render_for_every_phase() {
+        let registry = PromptRegistry::builtin();
+        for phase in PhaseInfo::get_all_phases() {
+            let prompt = registry.render(&phase.name, &variables()).unwrap();
+            assert_eq!(prompt.version, "v1");
+            assert!(prompt.text.contains("The Lost Crown"), "{} prompt is missing the campaign name", phase.name);
+        }
+
+        let prompt = registry.render(PHASE_2A_PC_ENTITIES, &variables()).unwrap();
+        assert!(prompt.text.contains("- Aria (Elf Ranger, Level 3): Exiled scout"));
+    }
+
+    #[test]
+    fn test_highest_version_wins_unless_pinned() {
+        let mut registry = PromptRegistry::builtin();
+        registry.insert(PHASE_1A_CORE_WORLD, "v2", "v2 for {{ campaign.name }}");
+        registry.insert(PHASE_1A_CORE_WORLD, "v10", "v10 for {{ campaign.name }}");
+        assert_eq!(registry.active_version(PHASE_1A_CORE_WORLD), Some("v10"));
+
+        registry.select_versions("phase_1a_core_world=v2").unwrap();
+        let prompt = registry.render(PHASE_1A_CORE_WORLD, &variables()).unwrap();
+        assert_eq!(prompt.text, "v2 for The Lost Crown");
+
+        assert!(registry.select_versions("phase_1a_core_world=v7").is_err());
+        assert!(registry.select_versions("phase_1a_core_world").is_err());
+    }
+
+    #[test]
+    fn test_unknown_variables_are_errors() {
+        let mut registry = PromptRegistry::default();
+        registry.insert(PHASE_1A_CORE_WORLD, "v1", "{{ campaign.nmae }}");
+        assert!(registry.render(PHASE_1A_CORE_WORLD, &variables()).is_err());
+        assert!(registry.render(PHASE_1B_CHARACTER_BUILDING, &variables()).is_err());
+    }
+}
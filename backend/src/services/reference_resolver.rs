@@ -0,0 +1,304 @@
+use crate::services::{SchemaViolation, TableSummary, ViolationKind};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Names at least this similar (Jaro-Winkler) to exactly one saved name resolve to it.
+const MATCH_THRESHOLD: f64 = 0.9;
+/// Below the match threshold, the closest name is still offered as a suggestion above this.
+const SUGGESTION_THRESHOLD: f64 = 0.75;
+
+/// A foreign key column and the table it references.
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct ForeignKey {
+    pub table_name: String,
+    pub column_name: String,
+    pub referenced_table: String,
+}
+
+/// Turns the names a model writes into foreign key columns (`"race_id": "High Elf"`) into the
+/// ids of rows earlier phases saved for the campaign, and checks that integer ids it was given
+/// exist. Only columns referencing an indexed table are touched; references to tables the
+/// current phase is still filling in are left for the database to check.
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceResolver {
+    foreign_keys: HashMap<(String, String), String>,
+    indexes: HashMap<String, Vec<(i32, String)>>,
+}
+
+impl ReferenceResolver {
+    pub fn new(foreign_keys: Vec<ForeignKey>, tables: Vec<TableSummary>) -> Self {
+        Self {
+            foreign_keys: foreign_keys
+                .into_iter()
+                .map(|fk| ((fk.table_name, fk.column_name), fk.referenced_table))
+                .collect(),
+            indexes: tables
+                .into_iter()
+                .map(|summary| (summary.table, summary.entries))
+                .collect(),
+        }
+    }
+
+    /// Resolve every reference in a phase tool input shaped `{table: [row, ...], ...}`, in place.
+    /// Returns one violation per reference that could not be resolved.
+    pub fn resolve(&self, input: &mut Value) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+        let Some(tables) = input.as_object_mut() else {
+            return violations;
+        };
+
+        for (table, rows) in tables.iter_mut() {
+            let Some(rows) = rows.as_array_mut() else {
+                continue;
+            };
+            for (index, row) in rows.iter_mut().enumerate() {
+                let Some(row) = row.as_object_mut() else {
+                    continue;
+                };
+                for (column, value) in row.iter_mut() {
+                    let Some(target) = self.referenced_table(table, column) else {
+                        continue;
+                    };
+                    let path = format!("$.{}[{}].{}", table, index, column);
+                    match value {
+                        Value::Array(items) => {
+                            for (i, item) in items.iter_mut().enumerate() {
+                                self.resolve_value(target, item, &format!("{}[{}]", path, i), &mut violations);
+                            }
+                        }
+                        _ => self.resolve_value(target, value, &path, &mut violations),
+                    }
+                }
+            }
+        }
+
+        violations
+    }
+
+    /// Let the model name rows instead of giving ids in the columns this resolver can resolve,
+    /// by widening their integer types in a phase tool schema shaped `{table: [row, ...]}`.
+    /// Columns referencing tables the phase is still filling in stay integers, so a name there
+    /// is caught by schema validation rather than by the insert.
+    pub fn widen_tool_schema(&self, schema: &mut Value) {
+        let Some(tables) = schema.get_mut("properties").and_then(Value::as_object_mut) else {
+            return;
+        };
+
+        for (table, table_schema) in tables.iter_mut() {
+            let Some(columns) = table_schema.pointer_mut("/items/properties").and_then(Value::as_object_mut) else {
+                continue;
+            };
+            for (column, column_schema) in columns.iter_mut() {
+                if self.referenced_table(table, column).is_none() {
+                    continue;
+                }
+                let is_array = column_schema["type"] == "array";
+                let target = if is_array { &mut column_schema["items"] } else { column_schema };
+                if target["type"] == "integer" {
+                    *target = json!({
+                        "type": ["integer", "string"],
+                        "description": "Id or exact name of an existing record"
+                    });
+                }
+            }
+        }
+    }
+
+    /// The indexed table a column points at. `*_ids` array columns have no foreign key, so
+    /// `related_location_ids` is matched to whatever a `location_id` column references.
+    fn referenced_table(&self, table: &str, column: &str) -> Option<&str> {
+        let target = match self.foreign_keys.get(&(table.to_string(), column.to_string())) {
+            Some(target) => Some(target),
+            None => {
+                let stem = column.strip_suffix("_ids")?;
+                let singular = format!("{}_id", stem.rsplit('_').next().unwrap_or(stem));
+                self.foreign_keys
+                    .iter()
+                    .find(|((_, fk_column), _)| *fk_column == singular)
+                    .map(|(_, target)| target)
+            }
+        }?;
+        self.indexes.contains_key(target).then_some(target.as_str())
+    }
+
+    fn resolve_value(&self, table: &str, value: &mut Value, path: &str, violations: &mut Vec<SchemaViolation>) {
+        let entries = &self.indexes[table];
+        let result = match value {
+            Value::Number(number) => match number.as_i64() {
+                Some(id) if entries.iter().any(|(entry_id, _)| i64::from(*entry_id) == id) => return,
+                _ => Err(format!("{} has no row with id {}", table, number)),
+            },
+            Value::String(name) => lookup(entries, name).map_err(|suggestion| match suggestion {
+                Some(suggestion) => format!("no {} named {:?}; did you mean {:?}?", table, name, suggestion),
+                None => format!("no {} named {:?}", table, name),
+            }),
+            _ => return,
+        };
+
+        match result {
+            Ok(id) => *value = Value::from(id),
+            Err(message) => violations.push(SchemaViolation {
+                path: path.to_string(),
+                kind: ViolationKind::UnresolvedReference,
+                message,
+            }),
+        }
+    }
+}
+
+/// Find the id for a model-provided name: an id written as `#12`, an exact match ignoring case
+/// and punctuation, or a single clearly closest fuzzy match. On failure returns the closest
+/// name worth suggesting, if any.
+fn lookup(entries: &[(i32, String)], name: &str) -> Result<i32, Option<String>> {
+    let name = name.trim();
+    if let Ok(id) = name.trim_start_matches('#').parse::<i32>() {
+        return entries.iter().find(|(entry_id, _)| *entry_id == id).map(|(id, _)| *id).ok_or(None);
+    }
+
+    // The prompt lists rows as `Name (#id)`, which models sometimes copy verbatim
+    let name = match name.rsplit_once(" (#") {
+        Some((name, _)) => name,
+        None => name,
+    };
+    let wanted = normalize(name);
+    if let Some((id, _)) = entries.iter().find(|(_, entry)| normalize(entry) == wanted) {
+        return Ok(*id);
+    }
+
+    let mut scored: Vec<(f64, i32, &str)> = entries
+        .iter()
+        .map(|(id, entry)| (strsim::jaro_winkler(&wanted, &normalize(entry)), *id, entry.as_str()))
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    match scored.as_slice() {
+        [] => Err(None),
+        [(best, id, _), rest @ ..]
+            if *best >= MATCH_THRESHOLD && rest.first().is_none_or(|(next, _, _)| best - next > 0.02) =>
+        {
+            Ok(*id)
+        }
+        [(best, _, entry), ..] if *best >= SUGGESTION_THRESHOLD => Err(Some(entry.to_string())),
+        _ => Err(None),
+    }
+}
+
+/// Lowercase, keep letters and digits, collapse everything else to single spaces and drop a
+/// leading "the".
+fn normalize(name: &str) -> String {
+    let words: Vec<String> = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let words = match words.split_first() {
+        Some((first, rest)) if first == "the" && !rest.is_empty() => rest,
+        _ => &words[..],
+    };
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolver() -> ReferenceResolver {
+        let fk = |table: &str, column: &str, referenced: &str| ForeignKey {
+            table_name: table.to_string(),
+            column_name: column.to_string(),
+            referenced_table: referenced.to_string(),
+        };
+        let summary = |table: &str, entries: &[(i32, &str)]| TableSummary {
+            table: table.to_string(),
+            entries: entries.iter().map(|(id, name)| (*id, name.to_string())).collect(),
+        };
+
+        ReferenceResolver::new(
+            vec![
+                fk("entities", "race_id", "races"),
+                fk("entity_locations", "location_id", "locations"),
+                fk("locations", "parent_location_id", "locations"),
+                fk("item_effects", "item_id", "items"),
+            ],
+            vec![
+                summary("races", &[(1, "Elf"), (2, "High Elf"), (3, "Hill Dwarf")]),
+                summary("locations", &[(7, "Greyharbor"), (8, "The Sunken Library")]),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_names_resolve_to_ids() {
+        let mut input = json!({
+            "entities": [
+                {"name": "Aria", "race_id": "elf"},
+                {"name": "Borin", "race_id": "Hill Dwarves"},
+                {"name": "Cael", "race_id": "High Elf (#2)"},
+                {"name": "Dara", "race_id": 3}
+            ],
+            "quest_hooks": [{"title": "Fog", "related_location_ids": ["Grayharbor", "sunken library", "#7"]}]
+        });
+
+        let violations = resolver().resolve(&mut input);
+
+        assert!(violations.is_empty(), "{:?}", violations);
+        let race_ids: Vec<&Value> = input["entities"].as_array().unwrap().iter().map(|e| &e["race_id"]).collect();
+        assert_eq!(race_ids, vec![&json!(1), &json!(3), &json!(2), &json!(3)]);
+        assert_eq!(input["quest_hooks"][0]["related_location_ids"], json!([7, 8, 7]));
+    }
+
+    #[test]
+    fn test_unresolved_references_are_reported() {
+        let mut input = json!({
+            "entities": [{"name": "Aria", "race_id": "Elfish"}, {"name": "Borin", "race_id": 42}],
+            "quest_hooks": [{"title": "Fog", "related_location_ids": ["Atlantis"]}]
+        });
+
+        let violations = resolver().resolve(&mut input);
+        let found: Vec<(&str, &str)> = violations.iter().map(|v| (v.path.as_str(), v.message.as_str())).collect();
+
+        assert_eq!(
+            found,
+            vec![
+                ("$.entities[0].race_id", "no races named \"Elfish\"; did you mean \"Elf\"?"),
+                ("$.entities[1].race_id", "races has no row with id 42"),
+                ("$.quest_hooks[0].related_location_ids[0]", "no locations named \"Atlantis\""),
+            ]
+        );
+        assert!(violations.iter().all(|v| v.kind == ViolationKind::UnresolvedReference));
+        assert_eq!(input["entities"][0]["race_id"], json!("Elfish"));
+    }
+
+    #[test]
+    fn test_only_resolvable_columns_accept_names() {
+        let integer = json!({"type": "integer"});
+        let mut schema = json!({
+            "type": "object",
+            "properties": {
+                "entities": {"type": "array", "items": {"type": "object", "properties": {"name": {"type": "string"}, "race_id": integer}}},
+                "quest_hooks": {"type": "array", "items": {"type": "object", "properties": {"related_location_ids": {"type": "array", "items": integer}}}},
+                "item_effects": {"type": "array", "items": {"type": "object", "properties": {"item_id": integer}}}
+            }
+        });
+
+        resolver().widen_tool_schema(&mut schema);
+
+        let widened = json!(["integer", "string"]);
+        assert_eq!(schema.pointer("/properties/entities/items/properties/race_id/type"), Some(&widened));
+        assert_eq!(schema.pointer("/properties/quest_hooks/items/properties/related_location_ids/items/type"), Some(&widened));
+        assert_eq!(schema.pointer("/properties/entities/items/properties/name"), Some(&json!({"type": "string"})));
+        // Items are created in the same phase as their effects, so they have no ids to name yet
+        assert_eq!(schema.pointer("/properties/item_effects/items/properties/item_id"), Some(&integer));
+    }
+
+    #[test]
+    fn test_ambiguous_names_are_not_guessed() {
+        let entries = vec![(1, "Elf".to_string()), (2, "High Elf".to_string())];
+        assert_eq!(lookup(&entries, "Elf"), Ok(1));
+        assert_eq!(lookup(&entries, "Elves"), Err(None));
+
+        let entries = vec![(1, "Stormhold".to_string()), (2, "Stormholt".to_string()), (3, "Ironforge".to_string()), (4, "Ironforges".to_string())];
+        assert_eq!(lookup(&entries, "Stormhald"), Ok(1));
+        assert_eq!(lookup(&entries, "Ironforg"), Err(Some("Ironforge".to_string())));
+    }
+}
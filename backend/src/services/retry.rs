@@ -0,0 +1,187 @@
+use reqwest::StatusCode;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Anthropic's "overloaded" status, which reqwest has no constant for.
+pub const STATUS_OVERLOADED: u16 = 529;
+
+/// How an LLM client retries transient failures: exponential backoff with jitter, bounded by
+/// an attempt count per call and by `max_total`, the time a phase may spend on failed attempts
+/// and backoff across all of its calls (see [`RetryClock`]).
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub max_total: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 6,
+            base_delay: Duration::from_secs(2),
+            max_delay: Duration::from_secs(60),
+            max_total: Duration::from_secs(300),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn is_retryable_status(status: StatusCode) -> bool {
+        status == StatusCode::TOO_MANY_REQUESTS
+            || status.as_u16() == STATUS_OVERLOADED
+            || matches!(
+                status,
+                StatusCode::INTERNAL_SERVER_ERROR
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            )
+    }
+
+    pub fn is_retryable_error(error: &reqwest::Error) -> bool {
+        error.is_connect() || error.is_timeout() || error.is_request()
+    }
+
+    /// Exponential backoff before retry number `attempt` (1-based), with "equal jitter": half the
+    /// delay is fixed and half is random. A server-provided `retry-after` takes precedence, up to
+    /// `max_delay`.
+    pub fn delay_for(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(self.max_delay);
+        }
+
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        let half = exponential / 2;
+        half + half.mul_f64(fastrand::f64())
+    }
+
+    /// Whether another attempt fits in the policy after `attempt` attempts, `retrying` time
+    /// spent retrying so far and a planned wait of `delay`.
+    pub fn should_retry(&self, attempt: u32, retrying: Duration, delay: Duration) -> bool {
+        attempt < self.max_attempts && retrying + delay <= self.max_total
+    }
+}
+
+/// Parse a `retry-after` header given in seconds. HTTP-date values and values that are not
+/// finite are ignored; values too large for a `Duration` saturate.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(|secs| Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX))
+}
+
+/// Time spent on failed attempts and backoff, shared by every LLM call a phase makes so that
+/// repair calls draw on the same `max_total` as the first one.
+#[derive(Debug, Default)]
+pub struct RetryClock {
+    spent: Mutex<Duration>,
+}
+
+impl RetryClock {
+    /// The clock of the phase the current call belongs to, or a fresh one for a call made
+    /// outside a phase.
+    pub fn current() -> Arc<Self> {
+        RETRY_CLOCK.try_with(Arc::clone).unwrap_or_default()
+    }
+
+    pub fn spent(&self) -> Duration {
+        *self.spent.lock().unwrap()
+    }
+
+    pub fn add(&self, duration: Duration) {
+        let mut spent = self.spent.lock().unwrap();
+        *spent = spent.saturating_add(duration);
+    }
+}
+
+/// Details of a retry, passed to whoever is listening for the current call.
+#[derive(Debug, Clone)]
+pub struct RetryNotice {
+    pub attempt: u32,
+    pub delay: Duration,
+    pub reason: String,
+}
+
+pub type RetryListener = Arc<dyn Fn(RetryNotice) + Send + Sync>;
+
+tokio::task_local! {
+    /// Set around an LLM call by the caller that wants to hear about its retries, so clients
+    /// can report them without knowing which campaign or phase they are serving.
+    pub static RETRY_LISTENER: RetryListener;
+
+    /// Set around a phase by the generation service; see [`RetryClock`].
+    pub static RETRY_CLOCK: Arc<RetryClock>;
+}
+
+pub fn notify_retry(notice: RetryNotice) {
+    let _ = RETRY_LISTENER.try_with(|listener| listener(notice));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_grows_and_is_capped() {
+        let policy = RetryPolicy::default();
+
+        let first = policy.delay_for(1, None);
+        assert!(first >= Duration::from_secs(1) && first <= Duration::from_secs(2));
+
+        let third = policy.delay_for(3, None);
+        assert!(third >= Duration::from_secs(4) && third <= Duration::from_secs(8));
+
+        let late = policy.delay_for(20, None);
+        assert!(late <= policy.max_delay);
+
+        assert_eq!(policy.delay_for(1, Some(Duration::from_secs(17))), Duration::from_secs(17));
+        assert_eq!(policy.delay_for(1, Some(Duration::MAX)), policy.max_delay);
+    }
+
+    #[test]
+    fn test_retry_budget() {
+        let policy = RetryPolicy::default();
+        assert!(policy.should_retry(1, Duration::ZERO, Duration::from_secs(2)));
+        assert!(!policy.should_retry(policy.max_attempts, Duration::ZERO, Duration::from_secs(2)));
+        assert!(!policy.should_retry(1, Duration::from_secs(299), Duration::from_secs(2)));
+    }
+
+    #[tokio::test]
+    async fn test_retry_clock_is_shared_within_a_phase() {
+        let clock = Arc::new(RetryClock::default());
+        RETRY_CLOCK
+            .scope(clock.clone(), async {
+                RetryClock::current().add(Duration::from_secs(3));
+                RetryClock::current().add(Duration::from_secs(4));
+            })
+            .await;
+        assert_eq!(clock.spent(), Duration::from_secs(7));
+
+        // Outside a phase every call starts from zero
+        RetryClock::current().add(Duration::from_secs(3));
+        assert_eq!(RetryClock::current().spent(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_retryable_statuses_and_retry_after() {
+        assert!(RetryPolicy::is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(RetryPolicy::is_retryable_status(StatusCode::from_u16(STATUS_OVERLOADED).unwrap()));
+        assert!(RetryPolicy::is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(!RetryPolicy::is_retryable_status(StatusCode::BAD_REQUEST));
+        assert!(!RetryPolicy::is_retryable_status(StatusCode::UNAUTHORIZED));
+
+        assert_eq!(parse_retry_after("30"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+        assert_eq!(parse_retry_after("inf"), None);
+        assert_eq!(parse_retry_after("NaN"), None);
+        assert_eq!(parse_retry_after("1e30"), Some(Duration::MAX));
+    }
+}
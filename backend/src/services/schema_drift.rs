@@ -0,0 +1,739 @@
+use crate::error::{ApiError, ApiResult};
+use crate::models::PhaseInfo;
+use crate::services::ForeignKey;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Tables, their columns and single-column foreign keys, as defined by the migrations or found
+/// in a live database.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SqlSchema {
+    tables: BTreeMap<String, BTreeSet<String>>,
+    /// `(table, column)` to `(referenced table, constraint name)`
+    foreign_keys: BTreeMap<(String, String), (String, String)>,
+}
+
+impl SqlSchema {
+    /// Apply every `.sql` file in `dir` in name order, which is migration version order.
+    pub fn from_migrations(dir: impl AsRef<Path>) -> ApiResult<Self> {
+        let dir = dir.as_ref();
+        let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+            .map_err(|e| read_error(dir, e))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("sql"))
+            .collect();
+        files.sort();
+
+        let mut schema = Self::default();
+        for file in files {
+            let sql = std::fs::read_to_string(&file).map_err(|e| read_error(&file, e))?;
+            schema.apply_migration(&sql);
+        }
+        Ok(schema)
+    }
+
+    /// The tables, columns and foreign keys of the `public` schema of a live database.
+    /// Views are left out, as they are when reading the migrations.
+    pub async fn from_database(pool: &PgPool) -> ApiResult<Self> {
+        let columns = sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT c.table_name::TEXT, c.column_name::TEXT
+            FROM information_schema.columns c
+            JOIN information_schema.tables t
+              ON t.table_schema = c.table_schema AND t.table_name = c.table_name
+            WHERE c.table_schema = 'public' AND t.table_type = 'BASE TABLE'
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+        let foreign_keys = sqlx::query_as::<_, (String, String, String, String)>(
+            r#"
+            SELECT kcu.table_name::TEXT, kcu.column_name::TEXT, ccu.table_name::TEXT, tc.constraint_name::TEXT
+            FROM information_schema.table_constraints tc
+            JOIN information_schema.key_column_usage kcu
+              ON kcu.constraint_name = tc.constraint_name AND kcu.table_schema = tc.table_schema
+            JOIN information_schema.constraint_column_usage ccu
+              ON ccu.constraint_name = tc.constraint_name AND ccu.table_schema = tc.table_schema
+            WHERE tc.constraint_type = 'FOREIGN KEY' AND tc.table_schema = 'public'
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut schema = Self::default();
+        for (table, column) in columns {
+            schema.tables.entry(table).or_default().insert(column);
+        }
+        for (table, column, referenced, name) in foreign_keys {
+            schema.foreign_keys.insert((table, column), (referenced, name));
+        }
+        Ok(schema)
+    }
+
+    /// Apply the `CREATE TABLE`, `ALTER TABLE` and `DROP TABLE` statements of a migration.
+    pub fn apply_migration(&mut self, sql: &str) {
+        for statement in split_statements(sql) {
+            let tokens = tokenize(&statement);
+            let words: Vec<&str> = tokens.iter().map(String::as_str).collect();
+            match words.as_slice() {
+                ["create", "table", rest @ ..] => {
+                    let rest = skip_if_exists(rest, "not");
+                    if let [table, "(", body @ ..] = rest {
+                        let items = top_level_items(body);
+                        let columns: Vec<String> = items
+                            .iter()
+                            .filter_map(|item| item.first().copied())
+                            .filter(|first| !is_constraint_keyword(first))
+                            .map(str::to_string)
+                            .collect();
+                        self.tables.entry(table.to_string()).or_default().extend(columns);
+                        for item in &items {
+                            self.add_foreign_key(table, item);
+                        }
+                    }
+                }
+                ["alter", "table", rest @ ..] => {
+                    let rest = skip_if_exists(rest, "");
+                    let rest = rest.strip_prefix(&["only"][..]).unwrap_or(rest);
+                    if let [table, actions @ ..] = rest {
+                        for action in top_level_items(actions) {
+                            self.apply_alter(table, &action);
+                        }
+                    }
+                }
+                ["drop", "table", rest @ ..] => {
+                    for table in skip_if_exists(rest, "") {
+                        self.tables.remove(*table);
+                        self.foreign_keys.retain(|(from, _), (to, _)| from != table && to != table);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// An `ALTER TABLE` of a table no migration creates still records its columns, so queries
+    /// against it are reported column by column rather than as a missing table.
+    fn apply_alter(&mut self, table: &str, action: &[&str]) {
+        let columns = self.tables.entry(table.to_string()).or_default();
+        match action {
+            ["add", "column", rest @ ..] | ["add", rest @ ..] => {
+                let definition = skip_if_exists(rest, "not");
+                if let [column, ..] = definition {
+                    if !is_constraint_keyword(column) {
+                        columns.insert(column.to_string());
+                    }
+                }
+                self.add_foreign_key(table, definition);
+            }
+            ["drop", "column", rest @ ..] => {
+                if let [column, ..] = skip_if_exists(rest, "") {
+                    columns.remove(*column);
+                    self.foreign_keys.remove(&(table.to_string(), column.to_string()));
+                }
+            }
+            ["drop", "constraint", rest @ ..] => {
+                if let [constraint, ..] = skip_if_exists(rest, "") {
+                    self.foreign_keys.retain(|(from, _), (_, name)| from != table || name != constraint);
+                }
+            }
+            ["rename", "column", from, "to", to, ..] if columns.remove(*from) => {
+                columns.insert(to.to_string());
+                if let Some(target) = self.foreign_keys.remove(&(table.to_string(), from.to_string())) {
+                    self.foreign_keys.insert((table.to_string(), to.to_string()), target);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Record the foreign key a column definition or table constraint declares, if any.
+    /// Keys spanning several columns are skipped.
+    fn add_foreign_key(&mut self, table: &str, item: &[&str]) {
+        let Some(references) = item.iter().position(|token| *token == "references") else {
+            return;
+        };
+        let referenced = match &item[references + 1..] {
+            [_, ".", referenced, ..] | [referenced, ..] => *referenced,
+            [] => return,
+        };
+        let (name, definition) = match item {
+            ["constraint", name, rest @ ..] => (Some(*name), rest),
+            _ => (None, item),
+        };
+        let column = match definition {
+            ["foreign", "key", "(", column, ")", ..] => *column,
+            [column, ..] if !is_constraint_keyword(column) => *column,
+            _ => return,
+        };
+        // Postgres' default name for an unnamed foreign key
+        let name = name.map(str::to_string).unwrap_or_else(|| format!("{}_{}_fkey", table, column));
+        self.foreign_keys.insert((table.to_string(), column.to_string()), (referenced.to_string(), name));
+    }
+
+    pub fn tables(&self) -> &BTreeMap<String, BTreeSet<String>> {
+        &self.tables
+    }
+
+    pub fn foreign_keys(&self) -> Vec<ForeignKey> {
+        self.foreign_keys
+            .iter()
+            .map(|((table, column), (referenced, _))| ForeignKey {
+                table_name: table.clone(),
+                column_name: column.clone(),
+                referenced_table: referenced.clone(),
+            })
+            .collect()
+    }
+
+    pub fn has_table(&self, table: &str) -> bool {
+        self.tables.contains_key(table)
+    }
+
+    pub fn has_column(&self, table: &str, column: &str) -> bool {
+        self.tables.get(table).is_some_and(|columns| columns.contains(column))
+    }
+}
+
+/// One SQL statement found in a Rust string literal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlStatement {
+    /// `file:line` of the literal
+    pub location: String,
+    pub sql: String,
+}
+
+/// A table or column that code uses but the schema does not define.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaDrift {
+    pub location: String,
+    pub table: String,
+    /// `None` when the whole table is missing
+    pub column: Option<String>,
+}
+
+impl SchemaDrift {
+    /// `table` or `table.column`
+    pub fn target(&self) -> String {
+        match &self.column {
+            Some(column) => format!("{}.{}", self.table, column),
+            None => self.table.clone(),
+        }
+    }
+}
+
+impl fmt::Display for SchemaDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.column.is_some() { "column" } else { "table" };
+        write!(f, "{}: {} {} does not exist", self.location, kind, self.target())
+    }
+}
+
+/// SQL statements in the Rust sources under `dir`, skipping `src/bin` tools and `#[cfg(test)]`
+/// modules. Locations are relative to `dir`.
+pub fn collect_sql_statements(dir: impl AsRef<Path>) -> ApiResult<Vec<SqlStatement>> {
+    let dir = dir.as_ref();
+    let mut files = Vec::new();
+    collect_rust_files(dir, &mut files)?;
+    files.sort();
+
+    let mut statements = Vec::new();
+    for file in files {
+        let source = std::fs::read_to_string(&file).map_err(|e| read_error(&file, e))?;
+        let name = file.strip_prefix(dir).unwrap_or(&file).display().to_string();
+        let source = source.split("\n#[cfg(test)]").next().unwrap_or_default();
+        for (line, sql) in sql_literals(source) {
+            statements.push(SqlStatement { location: format!("{}:{}", name, line), sql });
+        }
+    }
+    Ok(statements)
+}
+
+/// The `table` and `table.column` entries of a known drift list such as
+/// `known_schema_drift.txt`, skipping blank lines and `#` comments.
+pub fn read_known_drift(path: impl AsRef<Path>) -> ApiResult<BTreeSet<String>> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path).map_err(|e| read_error(path, e))?;
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+fn collect_rust_files(dir: &Path, files: &mut Vec<PathBuf>) -> ApiResult<()> {
+    for entry in std::fs::read_dir(dir).map_err(|e| read_error(dir, e))? {
+        let path = entry.map_err(|e| read_error(dir, e))?.path();
+        if path.is_dir() {
+            if path.file_name().and_then(|name| name.to_str()) != Some("bin") {
+                collect_rust_files(&path, files)?;
+            }
+        } else if path.extension().and_then(|ext| ext.to_str()) == Some("rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Compare the tables and columns each statement writes or reads, and the tables generation
+/// phases save through GraphQL, against `schema`.
+pub fn check_schema_drift(schema: &SqlSchema, statements: &[SqlStatement]) -> Vec<SchemaDrift> {
+    let mut drift = Vec::new();
+    let mut report = |problem: SchemaDrift| {
+        if !drift.contains(&problem) {
+            drift.push(problem);
+        }
+    };
+
+    for statement in statements {
+        for (table, columns) in table_references(&statement.sql) {
+            if !schema.has_table(&table) {
+                report(SchemaDrift { location: statement.location.clone(), table, column: None });
+                continue;
+            }
+            for column in columns.into_iter().filter(|column| !schema.has_column(&table, column)) {
+                report(SchemaDrift { location: statement.location.clone(), table: table.clone(), column: Some(column) });
+            }
+        }
+    }
+
+    for phase in PhaseInfo::get_all_phases() {
+        for table in phase.tables.iter().filter(|table| !schema.has_table(table)) {
+            report(SchemaDrift { location: format!("phase {}", phase.name), table: table.clone(), column: None });
+        }
+    }
+
+    drift
+}
+
+/// Tables a statement touches, each with the columns it names explicitly: the column lists
+/// of `INSERT` and `ON CONFLICT` and the assignments of `UPDATE ... SET`. Tables named through
+/// `{}` placeholders, schema-qualified names and CTEs are skipped.
+fn table_references(sql: &str) -> Vec<(String, Vec<String>)> {
+    let tokens = tokenize(sql);
+    let ctes: BTreeSet<&str> = tokens
+        .windows(3)
+        .filter(|w| w[1] == "as" && w[2] == "(" && is_identifier(&w[0]))
+        .map(|w| w[0].as_str())
+        .collect();
+
+    let mut references: Vec<(String, Vec<String>)> = Vec::new();
+    let mut current: Option<usize> = None;
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i].as_str();
+        let next = tokens.get(i + 1).map(String::as_str);
+        match token {
+            // `FOR UPDATE` row locks name no table
+            "into" | "from" | "join" | "update" if !(token == "update" && i > 0 && tokens[i - 1] == "for") => {
+                if let Some(table) = next.filter(|t| is_identifier(t) && !ctes.contains(t)) {
+                    let after = tokens.get(i + 2).map(String::as_str);
+                    // `schema.table` and function calls such as `unnest(...)` are not tables
+                    if after != Some(".") && (after != Some("(") || token == "into") {
+                        references.push((table.to_string(), Vec::new()));
+                        current = Some(references.len() - 1);
+                        if after == Some("(") {
+                            let (columns, end) = parenthesized(&tokens, i + 2);
+                            references.last_mut().unwrap().1.extend(columns);
+                            i = end;
+                        } else {
+                            i += 2;
+                        }
+                        continue;
+                    }
+                }
+            }
+            "conflict" if next == Some("(") => {
+                let (columns, end) = parenthesized(&tokens, i + 1);
+                if let Some(index) = current {
+                    references[index].1.extend(columns);
+                }
+                i = end;
+                continue;
+            }
+            "set" => {
+                if let Some(index) = current {
+                    let mut depth = 0;
+                    let mut j = i + 1;
+                    while j < tokens.len() {
+                        match tokens[j].as_str() {
+                            "(" => depth += 1,
+                            ")" => depth -= 1,
+                            "where" | "returning" | "from" if depth == 0 => break,
+                            ";" => break,
+                            column if depth == 0
+                                && tokens.get(j + 1).map(String::as_str) == Some("=")
+                                && matches!(tokens[j - 1].as_str(), "set" | ",") =>
+                            {
+                                references[index].1.push(column.to_string());
+                            }
+                            _ => {}
+                        }
+                        j += 1;
+                    }
+                    i = j;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    references.retain(|(table, _)| !table.contains('{'));
+    references
+}
+
+/// Identifiers inside the parentheses opening at `open`, and the index after the closing one.
+fn parenthesized(tokens: &[String], open: usize) -> (Vec<String>, usize) {
+    let mut columns = Vec::new();
+    let mut i = open + 1;
+    while i < tokens.len() && tokens[i] != ")" {
+        if is_identifier(&tokens[i]) {
+            columns.push(tokens[i].clone());
+        }
+        i += 1;
+    }
+    (columns, i + 1)
+}
+
+/// The contents of the Rust string literals in `source` that look like SQL, with the line
+/// each starts on. The table names passed to `GraphQLClient::insert_one` and `insert_many`
+/// come back as `INSERT INTO table`, since Hasura writes those rows.
+fn sql_literals(source: &str) -> Vec<(usize, String)> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut literals = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '\n' => line += 1,
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            // Character literals that would otherwise open a string: '"' and '\"'
+            '\'' if chars.get(i + 1) == Some(&'"') && chars.get(i + 2) == Some(&'\'') => i += 2,
+            '\'' if chars.get(i + 1) == Some(&'\\') && chars.get(i + 3) == Some(&'\'') => i += 3,
+            'r' if matches!(chars.get(i + 1), Some('#') | Some('"')) && (i == 0 || !chars[i - 1].is_alphanumeric()) => {
+                let hashes = chars[i + 1..].iter().take_while(|c| **c == '#').count();
+                if chars.get(i + 1 + hashes) == Some(&'"') {
+                    let start = i + 2 + hashes;
+                    let closing: Vec<char> = std::iter::once('"').chain(std::iter::repeat_n('#', hashes)).collect();
+                    let end = (start..chars.len())
+                        .find(|&j| chars[j..].starts_with(&closing))
+                        .unwrap_or(chars.len());
+                    let text: String = chars[start..end].iter().collect();
+                    push_if_sql(&mut literals, line, text.clone());
+                    line += text.matches('\n').count();
+                    i = end + closing.len();
+                    continue;
+                }
+            }
+            '"' => {
+                let start_line = line;
+                let mut text = String::new();
+                let mut j = i + 1;
+                while j < chars.len() && chars[j] != '"' {
+                    if chars[j] == '\\' && j + 1 < chars.len() {
+                        if chars[j + 1] == '\n' {
+                            line += 1;
+                        } else {
+                            text.push(chars[j + 1]);
+                        }
+                        j += 2;
+                        continue;
+                    }
+                    if chars[j] == '\n' {
+                        line += 1;
+                    }
+                    text.push(chars[j]);
+                    j += 1;
+                }
+                let call: String = chars[i.saturating_sub(GRAPHQL_INSERTS[1].len())..i].iter().collect();
+                if GRAPHQL_INSERTS.iter().any(|insert| call.ends_with(insert)) {
+                    literals.push((start_line, format!("INSERT INTO {}", text)));
+                } else {
+                    push_if_sql(&mut literals, start_line, text);
+                }
+                i = j + 1;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    literals
+}
+
+const GRAPHQL_INSERTS: [&str; 2] = ["insert_one(", "insert_many("];
+
+fn push_if_sql(literals: &mut Vec<(usize, String)>, line: usize, text: String) {
+    let first_word = text.split_whitespace().next().unwrap_or_default();
+    if matches!(first_word, "INSERT" | "UPDATE" | "DELETE" | "SELECT" | "WITH") {
+        let leading_lines = text.len() - text.trim_start_matches(char::is_whitespace).len();
+        let line = line + text[..leading_lines].matches('\n').count();
+        literals.push((line, text));
+    }
+}
+
+/// Lowercased words, quoted strings as `'`, and single punctuation characters.
+fn tokenize(sql: &str) -> Vec<String> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '-' && chars.get(i + 1) == Some(&'-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '\'' {
+            i += 1;
+            while i < chars.len() && chars[i] != '\'' {
+                i += 1;
+            }
+            i += 1;
+            tokens.push("'".to_string());
+        } else if c == '"' {
+            let start = i + 1;
+            i = start;
+            while i < chars.len() && chars[i] != '"' {
+                i += 1;
+            }
+            tokens.push(chars[start..i.min(chars.len())].iter().collect());
+            i += 1;
+        } else if c.is_alphanumeric() || c == '_' || c == '$' || c == '{' || c == '}' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '$' | '{' | '}')) {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect::<String>().to_lowercase());
+        } else {
+            tokens.push(c.to_string());
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+/// Split a migration into statements on `;`, keeping `$$`-quoted function bodies intact.
+fn split_statements(sql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut in_body = false;
+
+    for line in sql.lines() {
+        let line = line.split("--").next().unwrap_or_default();
+        for (index, part) in line.split("$$").enumerate() {
+            if index > 0 {
+                in_body = !in_body;
+                current.push_str("$$");
+            }
+            if in_body {
+                current.push_str(part);
+                continue;
+            }
+            let mut pieces = part.split(';');
+            current.push_str(pieces.next().unwrap_or_default());
+            for piece in pieces {
+                statements.push(std::mem::take(&mut current));
+                current.push_str(piece);
+            }
+        }
+        current.push('\n');
+    }
+    statements.push(current);
+
+    statements.into_iter().filter(|s| !s.trim().is_empty()).collect()
+}
+
+/// Split tokens up to the closing parenthesis on top-level commas.
+fn top_level_items<'a>(tokens: &[&'a str]) -> Vec<Vec<&'a str>> {
+    let mut items = vec![Vec::new()];
+    let mut depth = 0;
+    for token in tokens {
+        match *token {
+            "(" => depth += 1,
+            ")" if depth == 0 => break,
+            ")" => depth -= 1,
+            "," if depth == 0 => {
+                items.push(Vec::new());
+                continue;
+            }
+            _ => {}
+        }
+        items.last_mut().unwrap().push(*token);
+    }
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// Skip `IF EXISTS`, or `IF NOT EXISTS` when `not` is `"not"`.
+fn skip_if_exists<'a, 'b>(tokens: &'b [&'a str], not: &str) -> &'b [&'a str] {
+    match tokens {
+        ["if", "exists", rest @ ..] => rest,
+        ["if", n, "exists", rest @ ..] if *n == not => rest,
+        _ => tokens,
+    }
+}
+
+fn is_constraint_keyword(word: &str) -> bool {
+    matches!(word, "constraint" | "primary" | "unique" | "foreign" | "check" | "exclude")
+}
+
+fn is_identifier(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '{')
+        && !matches!(token, "select" | "set" | "lateral" | "only" | "values")
+}
+
+fn read_error(path: &Path, e: std::io::Error) -> ApiError {
+    ApiError::Internal(anyhow::anyhow!("Failed to read {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIGRATION: &str = r#"
+        CREATE OR REPLACE FUNCTION touch() RETURNS TRIGGER AS $$
+        BEGIN NEW.updated_at = NOW(); RETURN NEW; END;
+        $$ language 'plpgsql';
+
+        CREATE TABLE campaigns (
+            id SERIAL PRIMARY KEY,
+            name TEXT NOT NULL, -- display name
+            status TEXT DEFAULT 'created' CHECK (status IN ('created', 'done')),
+            UNIQUE (name)
+        );
+        CREATE TABLE IF NOT EXISTS notes (id SERIAL PRIMARY KEY, body TEXT, old TEXT);
+        ALTER TABLE notes ADD COLUMN IF NOT EXISTS campaign_id INTEGER REFERENCES campaigns(id),
+            DROP COLUMN old;
+        ALTER TABLE campaigns ADD CONSTRAINT campaigns_name_check CHECK (name <> '');
+        ALTER TABLE campaigns RENAME COLUMN status TO state;
+        CREATE TABLE scratch (id SERIAL);
+        DROP TABLE IF EXISTS scratch;
+    "#;
+
+    fn schema() -> SqlSchema {
+        let mut schema = SqlSchema::default();
+        schema.apply_migration(MIGRATION);
+        schema
+    }
+
+    #[test]
+    fn test_migrations_build_tables_and_columns() {
+        let schema = schema();
+        assert!(!schema.has_table("scratch"));
+        assert!(!schema.has_column("campaigns", "unique"));
+        assert!(!schema.has_column("campaigns", "status"));
+        assert!(schema.has_column("campaigns", "state"));
+        assert!(schema.has_column("notes", "campaign_id"));
+        assert!(!schema.has_column("notes", "old"));
+    }
+
+    #[test]
+    fn test_migrations_track_single_column_foreign_keys() {
+        let mut schema = schema();
+        schema.apply_migration(
+            r#"
+            CREATE TABLE links (
+                id SERIAL PRIMARY KEY,
+                note_id INTEGER NOT NULL REFERENCES notes(id) ON DELETE CASCADE,
+                other_id INTEGER,
+                pair_a INTEGER,
+                pair_b INTEGER,
+                FOREIGN KEY (other_id) REFERENCES public.notes (id),
+                FOREIGN KEY (pair_a, pair_b) REFERENCES pairs (a, b)
+            );
+            ALTER TABLE links DROP CONSTRAINT IF EXISTS links_note_id_fkey;
+            ALTER TABLE links ADD CONSTRAINT links_note_fk FOREIGN KEY (note_id) REFERENCES campaigns(id);
+            ALTER TABLE links RENAME COLUMN other_id TO source_id;
+            "#,
+        );
+
+        let keys: Vec<String> = schema
+            .foreign_keys()
+            .into_iter()
+            .map(|key| format!("{}.{} -> {}", key.table_name, key.column_name, key.referenced_table))
+            .collect();
+        assert_eq!(keys, ["links.note_id -> campaigns", "links.source_id -> notes", "notes.campaign_id -> campaigns"]);
+
+        schema.apply_migration("ALTER TABLE links DROP CONSTRAINT links_note_fk; DROP TABLE notes;");
+        assert!(schema.foreign_keys().is_empty());
+    }
+
+    #[test]
+    fn test_sql_literals_are_found_with_their_lines() {
+        let source = r##"
+fn save() {
+    let c = '"';
+    // "SELECT * FROM commented_out"
+    sqlx::query(
+        r#"
+        INSERT INTO notes (body) VALUES ($1)
+        "#
+    );
+    sqlx::query("UPDATE campaigns SET name = $2 \
+                 WHERE id = $1");
+    info!("Selected {} rows", 3);
+    graphql.insert_one("npcs", object);
+}
+"##;
+        let literals = sql_literals(source);
+        let found: Vec<(usize, &str)> = literals.iter().map(|(line, sql)| (*line, sql.trim())).collect();
+
+        assert_eq!(found[0], (7, "INSERT INTO notes (body) VALUES ($1)"));
+        assert_eq!(found[1].0, 10);
+        assert_eq!(found[2], (13, "INSERT INTO npcs"));
+        assert_eq!(found.len(), 3);
+    }
+
+    #[test]
+    fn test_drift_reports_missing_tables_and_columns() {
+        let statement = |sql: &str| SqlStatement { location: "db.rs:1".to_string(), sql: sql.to_string() };
+        let statements = [
+            statement("INSERT INTO notes (body, campaign_id, author) VALUES ($1, $2, $3) RETURNING id"),
+            statement("UPDATE campaigns SET state = 'done', name = upper(name), colour = $2 WHERE id = $1"),
+            statement("WITH recent AS (SELECT id FROM notes) SELECT * FROM recent JOIN weapons w ON w.id = recent.id"),
+            statement("SELECT n.id FROM notes n, unnest($1::text[]) AS t FROM {} x"),
+            statement("INSERT INTO notes (body) VALUES ($1) ON CONFLICT (title) DO UPDATE SET body = EXCLUDED.body"),
+        ];
+
+        let drift: Vec<String> = check_schema_drift(&schema(), &statements)
+            .into_iter()
+            .filter(|d| d.location == "db.rs:1")
+            .map(|d| d.target())
+            .collect();
+
+        assert_eq!(drift, vec!["notes.author", "campaigns.colour", "weapons", "notes.title"]);
+    }
+
+    #[test]
+    fn test_services_match_migrations_apart_from_known_drift() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let schema = SqlSchema::from_migrations(root.join("migrations")).unwrap();
+        let statements = collect_sql_statements(root.join("src")).unwrap();
+        let known = read_known_drift(root.join("known_schema_drift.txt")).unwrap();
+
+        let drift = check_schema_drift(&schema, &statements);
+        let new: Vec<String> = drift.iter().filter(|d| !known.contains(&d.target())).map(SchemaDrift::to_string).collect();
+        assert!(new.is_empty(), "SQL uses tables or columns the migrations do not define:\n{}", new.join("\n"));
+
+        let found: BTreeSet<String> = drift.iter().map(SchemaDrift::target).collect();
+        let fixed: Vec<&String> = known.iter().filter(|k| !found.contains(*k)).collect();
+        assert!(fixed.is_empty(), "No longer drifting, remove from known_schema_drift.txt: {:?}", fixed);
+    }
+}
@@ -0,0 +1,212 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// What kind of rule a value broke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationKind {
+    MissingRequired,
+    WrongType,
+    NotInEnum,
+    InvalidFormat,
+    /// A name or id that does not match any saved row of the referenced table
+    UnresolvedReference,
+}
+
+/// One place where a tool response does not match its input schema.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SchemaViolation {
+    /// Location of the offending value, e.g. `$.npcs[3].name`
+    pub path: String,
+    pub kind: ViolationKind,
+    pub message: String,
+}
+
+/// Check `value` against the subset of JSON Schema that `HasuraSchemaGenerator` produces:
+/// `type` (a name or a list of names), `properties`, `required`, `items`, `enum` and the
+/// `uuid`, `date` and `date-time` formats. Optional properties may be `null`, matching how
+/// nullable GraphQL columns are inserted. Returns every violation found, not just the first.
+pub fn validate_against_schema(schema: &Value, value: &Value) -> Vec<SchemaViolation> {
+    let mut violations = Vec::new();
+    validate_value(schema, value, "$", &mut violations);
+    violations
+}
+
+fn validate_value(schema: &Value, value: &Value, path: &str, violations: &mut Vec<SchemaViolation>) {
+    if let Some(expected) = schema.get("type") {
+        let names: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !names.is_empty() && !names.iter().any(|name| matches_type(name, value)) {
+            violations.push(SchemaViolation {
+                path: path.to_string(),
+                kind: ViolationKind::WrongType,
+                message: format!("expected {}, got {}", names.join(" or "), type_name(value)),
+            });
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            let options: Vec<String> = allowed.iter().map(Value::to_string).collect();
+            violations.push(SchemaViolation {
+                path: path.to_string(),
+                kind: ViolationKind::NotInEnum,
+                message: format!("{} is not one of {}", value, options.join(", ")),
+            });
+        }
+    }
+
+    if let (Some(format), Some(text)) = (schema.get("format").and_then(Value::as_str), value.as_str()) {
+        if !matches_format(format, text) {
+            violations.push(SchemaViolation {
+                path: path.to_string(),
+                kind: ViolationKind::InvalidFormat,
+                message: format!("{:?} is not a valid {}", text, format),
+            });
+        }
+    }
+
+    match value {
+        Value::Object(object) => validate_object(schema, object, path, violations),
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_value(item_schema, item, &format!("{}[{}]", path, index), violations);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn validate_object(schema: &Value, object: &Map<String, Value>, path: &str, violations: &mut Vec<SchemaViolation>) {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    for name in &required {
+        if object.get(*name).is_none_or(Value::is_null) {
+            violations.push(SchemaViolation {
+                path: format!("{}.{}", path, name),
+                kind: ViolationKind::MissingRequired,
+                message: format!("required field {} is missing", name),
+            });
+        }
+    }
+
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return;
+    };
+    for (name, field) in object {
+        let Some(field_schema) = properties.get(name) else {
+            continue;
+        };
+        // Nulls are fine for optional fields and already reported for required ones
+        if !field.is_null() {
+            validate_value(field_schema, field, &format!("{}.{}", path, name), violations);
+        }
+    }
+}
+
+fn matches_type(name: &str, value: &Value) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn matches_format(format: &str, text: &str) -> bool {
+    match format {
+        "uuid" => uuid::Uuid::parse_str(text).is_ok(),
+        "date" => chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d").is_ok(),
+        "date-time" => chrono::DateTime::parse_from_rfc3339(text).is_ok(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn phase_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["npcs"],
+            "properties": {
+                "npcs": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": {
+                            "name": {"type": "string"},
+                            "age": {"type": "integer"},
+                            "role": {"type": "string", "enum": ["ally", "villain", "neutral"]},
+                            "born_on": {"type": "string", "format": "date"}
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_valid_response_has_no_violations() {
+        let value = json!({"npcs": [{"name": "Mira", "age": 31, "role": "ally", "born_on": null}]});
+        assert!(validate_against_schema(&phase_schema(), &value).is_empty());
+    }
+
+    #[test]
+    fn test_reports_every_violation_with_its_path() {
+        let value = json!({
+            "npcs": [
+                {"age": "old"},
+                {"name": "Vex", "role": "tyrant", "born_on": "last spring"}
+            ]
+        });
+        let violations = validate_against_schema(&phase_schema(), &value);
+        let found: Vec<(&str, ViolationKind)> = violations.iter().map(|v| (v.path.as_str(), v.kind)).collect();
+
+        assert_eq!(
+            found,
+            vec![
+                ("$.npcs[0].name", ViolationKind::MissingRequired),
+                ("$.npcs[0].age", ViolationKind::WrongType),
+                ("$.npcs[1].born_on", ViolationKind::InvalidFormat),
+                ("$.npcs[1].role", ViolationKind::NotInEnum),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_top_level_table() {
+        let violations = validate_against_schema(&phase_schema(), &json!({}));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].path, "$.npcs");
+        assert_eq!(violations[0].kind, ViolationKind::MissingRequired);
+    }
+}
@@ -1,5 +1,9 @@
 use crate::config::Config;
-use crate::services::{AnthropicClient, CampaignService, DatabaseServiceEnhanced, GenerationServiceEnhanced, GraphQLClient, HasuraSchemaGenerator};
+use crate::models::PriceTable;
+use crate::services::{
+    generation_event_channel, AnthropicClient, CampaignService, LlmProvider, MockLlmProvider, OpenAiClient, DatabaseServiceEnhanced, GenerationEventSender,
+    GenerationJobQueue, GenerationServiceEnhanced, GraphQLClient, HasuraSchemaGenerator, PromptRegistry,
+};
 use sqlx::PgPool;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -9,7 +13,10 @@ pub struct AppState {
     pub campaign_service: Arc<CampaignService>,
     pub database_service_enhanced: Arc<DatabaseServiceEnhanced>,
     pub generation_service: Arc<GenerationServiceEnhanced>,
+    pub job_queue: Arc<GenerationJobQueue>,
+    pub generation_events: GenerationEventSender,
     pub schema_generator: Arc<RwLock<HasuraSchemaGenerator>>,
+    pub price_table: Arc<PriceTable>,
 }
 
 impl AppState {
@@ -24,19 +31,52 @@ impl AppState {
         schema_generator.initialize().await?;
         let schema_generator = Arc::new(RwLock::new(schema_generator));
         
-        let anthropic_client = Arc::new(AnthropicClient::new(config.anthropic_api_key.clone()));
+        let llm: Arc<dyn LlmProvider> = match config.llm_provider.as_str() {
+            "mock" => Arc::new(MockLlmProvider::from_dir(&config.llm_fixtures_dir)?),
+            "anthropic" => Arc::new(
+                AnthropicClient::new(config.anthropic_api_key.clone()).with_streaming(config.anthropic_streaming),
+            ),
+            "openai" => Arc::new(OpenAiClient::new(
+                config.openai_base_url.clone(),
+                config.openai_api_key.clone(),
+                config.openai_model.clone(),
+            )),
+            other => {
+                return Err(crate::error::ApiError::BadRequest(format!("Unknown LLM_PROVIDER: {}", other)));
+            }
+        };
+        tracing::info!("Using LLM provider: {} ({})", llm.name(), llm.model());
+
+        let price_table = match &config.llm_price_table {
+            Some(path) => PriceTable::from_file(path)?,
+            None => PriceTable::default(),
+        };
+        let prompts = PromptRegistry::load(&config.prompt_templates_dir, config.prompt_versions.as_deref())?;
+        let generation_events = generation_event_channel();
         let generation_service = Arc::new(GenerationServiceEnhanced::new(
             database_service_enhanced.clone(),
             graphql_client.clone(),
-            anthropic_client,
+            llm,
             schema_generator.clone(),
+            config.generation_max_concurrency,
+            generation_events.clone(),
+        ).with_max_repair_attempts(config.generation_repair_attempts)
+        .with_context_token_budget(config.generation_context_tokens)
+        .with_prompts(Arc::new(prompts)));
+        let job_queue = Arc::new(GenerationJobQueue::new(
+            pool,
+            database_service_enhanced.clone(),
+            generation_service.clone(),
         ));
         
         Ok(Self {
             campaign_service: Arc::new(CampaignService::new(graphql_client.clone())),
             database_service_enhanced,
             generation_service,
+            job_queue,
+            generation_events,
             schema_generator,
+            price_table: Arc::new(price_table),
         })
     }
 }
\ No newline at end of file
@@ -0,0 +1,366 @@
+use dnd_campaign_generator::db::create_pool;
+use async_trait::async_trait;
+use dnd_campaign_generator::error::{ApiError, ApiResult};
+use dnd_campaign_generator::models::{
+    CascadeMode, PhaseInfo, PHASE_1A_CORE_WORLD, PHASE_1B_CHARACTER_BUILDING, PHASE_1C_SOCIAL_FRAMEWORK,
+    PHASE_2A_PC_ENTITIES, PHASE_2B_PC_LOCATIONS, PHASE_2C_PC_ITEMS, PHASE_3A_QUESTS_ENCOUNTERS,
+    PHASE_3B_WORLD_POPULATION, PHASE_3C_RELATIONSHIPS, TOTAL_PHASES,
+};
+use dnd_campaign_generator::services::{
+    generation_event_channel, DatabaseServiceEnhanced, GenerationServiceEnhanced, GraphQLClient, HasuraSchemaGenerator,
+    LlmProvider, Message, MockLlmProvider, Tool, ToolCallResult,
+};
+use serde_json::Value;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Notify, RwLock};
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/llm")
+}
+
+/// The fixtures `MockLlmProvider::from_dir` loads, minus `missing`, so that tool's phase fails.
+fn fixtures_without(missing: &str) -> HashMap<String, Value> {
+    std::fs::read_dir(fixtures_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.file_stem().and_then(|stem| stem.to_str()) != Some(missing))
+        .map(|path| {
+            let tool = path.file_stem().unwrap().to_string_lossy().into_owned();
+            (tool, serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap())
+        })
+        .collect()
+}
+
+/// Answers like the wrapped mock, but holds calls to `tool` so the phase is still running while
+/// the test acts: for `hold`, or until the phase is dropped when `None`. `started` is notified
+/// once a call is being held.
+struct SlowTool {
+    inner: MockLlmProvider,
+    tool: &'static str,
+    hold: Option<Duration>,
+    started: Arc<Notify>,
+}
+
+#[async_trait]
+impl LlmProvider for SlowTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    async fn generate_content(&self, prompt: &str, max_tokens: u32, temperature: f32) -> ApiResult<String> {
+        self.inner.generate_content(prompt, max_tokens, temperature).await
+    }
+
+    async fn generate_with_tool_messages(
+        &self,
+        system: Option<&str>,
+        messages: Vec<Message>,
+        tool: Tool,
+        max_tokens: u32,
+        temperature: f32,
+    ) -> ApiResult<ToolCallResult> {
+        if tool.name == self.tool {
+            self.started.notify_one();
+            match self.hold {
+                Some(hold) => tokio::time::sleep(hold).await,
+                None => std::future::pending().await,
+            }
+        }
+        self.inner.generate_with_tool_messages(system, messages, tool, max_tokens, temperature).await
+    }
+}
+
+/// Postgres at `DATABASE_URL`, or `None` to skip the test.
+async fn database() -> Option<PgPool> {
+    let Ok(database_url) = std::env::var("DATABASE_URL") else {
+        println!("DATABASE_URL not set, skipping test");
+        return None;
+    };
+    Some(create_pool(&database_url).await.expect("migrated database"))
+}
+
+/// A generation service saving through the same paths a real generation uses, answered by
+/// `llm`. Needs Postgres at `DATABASE_URL` and Hasura on localhost:8080 tracking its tables
+/// (`just dev`); `None` skips the test otherwise.
+async fn generation_service(llm: Arc<dyn LlmProvider>) -> Option<(PgPool, Arc<GenerationServiceEnhanced>)> {
+    let pool = database().await?;
+    if reqwest::get("http://localhost:8080/healthz").await.is_err() {
+        println!("Hasura not running, skipping test");
+        return None;
+    }
+
+    let database = Arc::new(DatabaseServiceEnhanced::new(pool.clone()));
+    let graphql = Arc::new(GraphQLClient::new(
+        std::env::var("HASURA_ADMIN_SECRET").unwrap_or_else(|_| "myadminsecretkey".to_string()),
+    ));
+    let mut schema_generator = HasuraSchemaGenerator::new(graphql.as_ref().clone());
+    schema_generator.initialize().await.expect("Hasura introspection");
+
+    let generation = GenerationServiceEnhanced::new(
+        database,
+        graphql,
+        llm,
+        Arc::new(RwLock::new(schema_generator)),
+        2,
+        generation_event_channel(),
+    );
+    Some((pool, Arc::new(generation)))
+}
+
+async fn create_campaign(pool: &PgPool, name: &str) -> i32 {
+    sqlx::query_scalar("INSERT INTO campaigns (name, setting, themes, tone) VALUES ($1, 'Test Setting', '{mystery}', 'heroic') RETURNING id")
+        .bind(name)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+async fn delete_campaign(pool: &PgPool, campaign_id: i32) {
+    sqlx::query("DELETE FROM campaigns WHERE id = $1").bind(campaign_id).execute(pool).await.unwrap();
+}
+
+async fn phase_statuses(pool: &PgPool, campaign_id: i32) -> HashMap<String, String> {
+    sqlx::query_as::<_, (String, String)>("SELECT phase_name, status FROM campaign_generation_phases WHERE campaign_id = $1")
+        .bind(campaign_id)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .collect()
+}
+
+async fn ids(pool: &PgPool, table: &str, campaign_id: i32) -> Vec<i32> {
+    sqlx::query_scalar(&format!("SELECT id FROM {} WHERE campaign_id = $1 ORDER BY id", table))
+        .bind(campaign_id)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+}
+
+/// Runs all nine phases against the fixture-driven mock provider, through the same save paths
+/// a real generation uses.
+#[tokio::test]
+async fn test_mock_generation_completes_every_phase() {
+    let llm = Arc::new(MockLlmProvider::from_dir(fixtures_dir()).expect("mock fixtures"));
+    let Some((pool, generation)) = generation_service(llm).await else {
+        return;
+    };
+    let campaign_id = create_campaign(&pool, "Mock Generation Test").await;
+
+    let result = generation.generate_campaign_content(campaign_id).await;
+    let database = DatabaseServiceEnhanced::new(pool.clone());
+    let campaign = database.get_campaign(campaign_id).await.unwrap();
+    let completed = database.get_completed_phases(campaign_id).await.unwrap();
+    let entities = ids(&pool, "entities", campaign_id).await;
+
+    delete_campaign(&pool, campaign_id).await;
+
+    assert!(result.is_ok(), "mock generation failed: {:?}", result);
+    assert_eq!(campaign.status, "completed");
+    assert_eq!(completed.len(), TOTAL_PHASES as usize);
+    assert!(!entities.is_empty(), "the fixtures save entities");
+}
+
+/// Regenerating 2B with `mark_stale` replaces its locations and buildings but leaves the
+/// shops, taverns and temples 3B saved in them, flagged as stale.
+#[tokio::test]
+async fn test_regenerating_a_phase_keeps_stale_dependents_rows() {
+    let llm = Arc::new(MockLlmProvider::from_dir(fixtures_dir()).expect("mock fixtures"));
+    let Some((pool, generation)) = generation_service(llm).await else {
+        return;
+    };
+    let campaign_id = create_campaign(&pool, "Mock Regeneration Test").await;
+
+    generation.generate_campaign_content(campaign_id).await.expect("mock generation");
+    let locations = ids(&pool, "locations", campaign_id).await;
+    let population: Vec<Vec<i32>> = futures::future::join_all(
+        ["shops", "taverns", "temples"].into_iter().map(|table| ids(&pool, table, campaign_id)),
+    )
+    .await;
+
+    let result = generation.regenerate_phase(campaign_id, PHASE_2B_PC_LOCATIONS, CascadeMode::MarkStale).await;
+    let regenerated_locations = ids(&pool, "locations", campaign_id).await;
+    let surviving: Vec<Vec<i32>> = futures::future::join_all(
+        ["shops", "taverns", "temples"].into_iter().map(|table| ids(&pool, table, campaign_id)),
+    )
+    .await;
+    let statuses = phase_statuses(&pool, campaign_id).await;
+
+    delete_campaign(&pool, campaign_id).await;
+
+    assert!(result.is_ok(), "regeneration failed: {:?}", result);
+    assert!(population.iter().any(|rows| !rows.is_empty()), "the fixtures save 3B rows");
+    assert_eq!(surviving, population, "3B rows were deleted with the 2B rows they referenced");
+    assert!(regenerated_locations.iter().all(|id| !locations.contains(id)), "2B was not regenerated");
+    assert_eq!(statuses[PHASE_2B_PC_LOCATIONS], "completed");
+    assert_eq!(statuses[PHASE_3B_WORLD_POPULATION], "stale");
+}
+
+/// Clearing a phase's rows nulls the references later phases hold to them instead of
+/// deleting those phases' rows. Needs only Postgres.
+#[tokio::test]
+async fn test_clearing_a_phase_keeps_rows_that_reference_it() {
+    let Some(pool) = database().await else {
+        return;
+    };
+    let campaign_id = create_campaign(&pool, "Clear Phase Test").await;
+
+    let location_id: i32 = sqlx::query_scalar(
+        "INSERT INTO locations (campaign_id, name, location_type) VALUES ($1, 'Harbor', 'city') RETURNING id",
+    )
+    .bind(campaign_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let building_id: i32 = sqlx::query_scalar(
+        "INSERT INTO buildings (campaign_id, location_id, building_type) VALUES ($1, $2, 'warehouse') RETURNING id",
+    )
+    .bind(campaign_id)
+    .bind(location_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let shop_id: i32 = sqlx::query_scalar(
+        "INSERT INTO shops (campaign_id, building_id, shop_type) VALUES ($1, $2, 'general') RETURNING id",
+    )
+    .bind(campaign_id)
+    .bind(building_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let entity_id: i32 = sqlx::query_scalar(
+        "INSERT INTO entities (campaign_id, name, entity_type) VALUES ($1, 'Harbormaster', 'npc') RETURNING id",
+    )
+    .bind(campaign_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO entity_locations (campaign_id, entity_id, location_id, association_type) VALUES ($1, $2, $3, 'works')",
+    )
+    .bind(campaign_id)
+    .bind(entity_id)
+    .bind(location_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let database = DatabaseServiceEnhanced::new(pool.clone());
+    let phase_2b = PhaseInfo::get_phase_info(PHASE_2B_PC_LOCATIONS).unwrap();
+    let result = database.clear_phase_data(campaign_id, &[phase_2b]).await;
+
+    let shop: Option<Option<i32>> = sqlx::query_scalar("SELECT building_id FROM shops WHERE id = $1")
+        .bind(shop_id)
+        .fetch_optional(&pool)
+        .await
+        .unwrap();
+    let entity_location: Option<(i32, Option<i32>)> =
+        sqlx::query_as("SELECT entity_id, location_id FROM entity_locations WHERE campaign_id = $1")
+            .bind(campaign_id)
+            .fetch_optional(&pool)
+            .await
+            .unwrap();
+    let buildings = ids(&pool, "buildings", campaign_id).await;
+
+    delete_campaign(&pool, campaign_id).await;
+
+    assert!(result.is_ok(), "clearing 2B failed: {:?}", result);
+    assert!(buildings.is_empty());
+    assert_eq!(shop, Some(None), "the 3B shop should survive without its building");
+    assert_eq!(entity_location, Some((entity_id, None)), "the 3C link should survive without its location");
+}
+
+/// A failing phase stops new phases from starting but lets the ones already running finish:
+/// 2C fails while 3B, started alongside it, is still generating. 3B completes, 3A and 3C (which
+/// need 2C) never start, and the phases before keep their completed status.
+#[tokio::test]
+async fn test_failed_phase_drains_siblings_and_skips_dependents() {
+    let llm = Arc::new(SlowTool {
+        inner: MockLlmProvider::new(fixtures_without("generate_phase_2c")),
+        tool: "generate_phase_3b",
+        hold: Some(Duration::from_millis(500)),
+        started: Arc::new(Notify::new()),
+    });
+    let Some((pool, generation)) = generation_service(llm).await else {
+        return;
+    };
+    let campaign_id = create_campaign(&pool, "Mock Failure Test").await;
+
+    let result = generation.generate_campaign_content(campaign_id).await;
+    let statuses = phase_statuses(&pool, campaign_id).await;
+    let campaign = DatabaseServiceEnhanced::new(pool.clone()).get_campaign(campaign_id).await.unwrap();
+
+    delete_campaign(&pool, campaign_id).await;
+
+    assert!(result.is_err(), "generation should fail with 2C");
+    assert_eq!(campaign.status, "error");
+    for phase in [
+        PHASE_1A_CORE_WORLD,
+        PHASE_1B_CHARACTER_BUILDING,
+        PHASE_1C_SOCIAL_FRAMEWORK,
+        PHASE_2A_PC_ENTITIES,
+        PHASE_2B_PC_LOCATIONS,
+    ] {
+        assert_eq!(statuses[phase], "completed", "{} should stay completed", phase);
+    }
+    assert_eq!(statuses[PHASE_2C_PC_ITEMS], "failed");
+    assert_eq!(statuses[PHASE_3B_WORLD_POPULATION], "completed", "3B was dropped instead of drained");
+    assert_eq!(statuses[PHASE_3A_QUESTS_ENCOUNTERS], "pending");
+    assert_eq!(statuses[PHASE_3C_RELATIONSHIPS], "pending");
+}
+
+/// Cancelling while 2B is generating rolls back the rows 2B had saved so far and resets it to
+/// pending, keeps the phases that had completed, and leaves the campaign cancelled.
+#[tokio::test]
+async fn test_cancelling_mid_phase_rolls_back_its_partial_rows() {
+    let started = Arc::new(Notify::new());
+    let llm = Arc::new(SlowTool {
+        inner: MockLlmProvider::from_dir(fixtures_dir()).expect("mock fixtures"),
+        tool: "generate_phase_2b",
+        hold: None,
+        started: started.clone(),
+    });
+    let Some((pool, generation)) = generation_service(llm).await else {
+        return;
+    };
+    let campaign_id = create_campaign(&pool, "Mock Cancellation Test").await;
+
+    let run = tokio::spawn({
+        let generation = generation.clone();
+        async move { generation.generate_campaign_content(campaign_id).await }
+    });
+    started.notified().await;
+    // What 2B had written before the cancellation arrived
+    sqlx::query("INSERT INTO locations (campaign_id, name, location_type) VALUES ($1, 'Half-built Keep', 'fortress')")
+        .bind(campaign_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let cancelled = generation.cancel_generation(campaign_id);
+    let result = run.await.unwrap();
+
+    let locations = ids(&pool, "locations", campaign_id).await;
+    let entities = ids(&pool, "entities", campaign_id).await;
+    let statuses = phase_statuses(&pool, campaign_id).await;
+    let campaign = DatabaseServiceEnhanced::new(pool.clone()).get_campaign(campaign_id).await.unwrap();
+
+    delete_campaign(&pool, campaign_id).await;
+
+    assert!(cancelled, "the run should have registered a cancellation token");
+    assert!(matches!(result, Err(ApiError::Cancelled)), "expected a cancelled run, got {:?}", result);
+    assert_eq!(campaign.status, "cancelled");
+    assert!(locations.is_empty(), "2B's partial rows were not rolled back");
+    assert_eq!(statuses[PHASE_2B_PC_LOCATIONS], "pending");
+    assert!(!entities.is_empty(), "2A's rows should survive the rollback");
+    for phase in [PHASE_1A_CORE_WORLD, PHASE_1B_CHARACTER_BUILDING, PHASE_1C_SOCIAL_FRAMEWORK, PHASE_2A_PC_ENTITIES] {
+        assert_eq!(statuses[phase], "completed", "{} should stay completed", phase);
+    }
+}
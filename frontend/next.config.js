@@ -16,7 +16,7 @@ const nextConfig = {
     NEXT_PUBLIC_GRAPHQL_URL: process.env.NEXT_PUBLIC_GRAPHQL_URL,
     NEXT_PUBLIC_API_URL: process.env.NEXT_PUBLIC_API_URL,
     NEXT_PUBLIC_WS_URL: process.env.NEXT_PUBLIC_WS_URL,
-    NEXT_PUBLIC_HASURA_ADMIN_SECRET: process.env.NEXT_PUBLIC_HASURA_ADMIN_SECRET,
+    NEXT_PUBLIC_HASURA_DEV_TOKEN: process.env.NEXT_PUBLIC_HASURA_DEV_TOKEN,
   },
   // Performance optimizations
   experimental: {
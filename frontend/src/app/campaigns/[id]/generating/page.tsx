@@ -11,6 +11,7 @@ export default function GeneratingPage() {
   const [progress, setProgress] = useState(0);
   const [currentPhase, setCurrentPhase] = useState(0);
   const [error, setError] = useState<string | null>(null);
+  const [log, setLog] = useState<string[]>([]);
   
   const campaignId = parseInt(params.id as string);
 
@@ -68,6 +69,50 @@ export default function GeneratingPage() {
 
   // Progress is now handled by real backend data via the subscription effect above
 
+  // Live generation log streamed from the backend
+  useEffect(() => {
+    if (!campaignId || isNaN(campaignId)) return;
+
+    const source = new EventSource(`${process.env.NEXT_PUBLIC_API_URL}/api/campaigns/${campaignId}/generate/events`);
+    const append = (line: string) => setLog((lines) => [...lines.slice(-49), line]);
+
+    source.addEventListener('phase_started', (e) => {
+      const event = JSON.parse((e as MessageEvent).data);
+      append(`Started ${event.phase}`);
+    });
+    source.addEventListener('phase_completed', (e) => {
+      const event = JSON.parse((e as MessageEvent).data);
+      append(`Completed ${event.phase} (${event.progress}%)`);
+    });
+    source.addEventListener('rows_saved', (e) => {
+      const event = JSON.parse((e as MessageEvent).data);
+      append(`Saved ${event.rows} ${event.table}`);
+    });
+    source.addEventListener('token_usage', (e) => {
+      const event = JSON.parse((e as MessageEvent).data);
+      append(`${event.phase}: ${event.input_tokens} input / ${event.output_tokens} output tokens`);
+    });
+    source.addEventListener('llm_retry', (e) => {
+      const event = JSON.parse((e as MessageEvent).data);
+      append(`${event.phase}: retry ${event.attempt} in ${Math.round(event.delay_ms / 1000)}s (${event.reason})`);
+    });
+    source.addEventListener('llm_progress', (e) => {
+      const event = JSON.parse((e as MessageEvent).data);
+      append(`${event.phase}: receiving output (~${event.estimated_tokens} tokens so far)`);
+    });
+    source.addEventListener('repair_attempt', (e) => {
+      const event = JSON.parse((e as MessageEvent).data);
+      append(`${event.phase}: output rejected, repair attempt ${event.attempt} (${event.reason})`);
+    });
+    source.addEventListener('error', (e) => {
+      // Connection errors arrive as plain events without data
+      const data = (e as MessageEvent).data;
+      if (data) append(`Error: ${JSON.parse(data).message}`);
+    });
+
+    return () => source.close();
+  }, [campaignId]);
+
   // Trigger generation on mount
   useEffect(() => {
     let isMounted = true;
@@ -180,6 +225,15 @@ export default function GeneratingPage() {
           })}
         </div>
 
+        {/* Live Log */}
+        {log.length > 0 && (
+          <div className="mt-8 bg-gray-900 border border-gray-700 rounded-lg p-4 max-h-48 overflow-y-auto font-mono text-xs text-gray-400">
+            {log.map((line, index) => (
+              <div key={index}>{line}</div>
+            ))}
+          </div>
+        )}
+
         {/* Feature Highlights */}
         <div className="mt-12 space-y-6">
           <h3 className="text-lg font-semibold text-white text-center">
@@ -0,0 +1,12 @@
+// Hasura authenticates clients with a JWT whose claims carry X-Hasura-Role and
+// X-Hasura-User-Id, so every client only reaches the campaigns it is a member of.
+// In development the token comes from `just hasura-dev-token`; a deployment with
+// sign-in returns the signed-in user's token here instead.
+export function getAuthToken(): string | undefined {
+  return process.env.NEXT_PUBLIC_HASURA_DEV_TOKEN || undefined;
+}
+
+export function authHeaders(): Record<string, string> {
+  const token = getAuthToken();
+  return token ? { Authorization: `Bearer ${token}` } : {};
+}
@@ -1,13 +1,10 @@
 import { createClient, cacheExchange, fetchExchange, subscriptionExchange } from 'urql';
 import { createClient as createWSClient } from 'graphql-ws';
+import { authHeaders } from './auth';
 
 const wsClient = typeof window !== 'undefined' ? createWSClient({
   url: process.env.NEXT_PUBLIC_WS_URL || 'ws://localhost:8080/v1/graphql',
-  connectionParams: {
-    headers: {
-      'x-hasura-admin-secret': process.env.NEXT_PUBLIC_HASURA_ADMIN_SECRET || 'hasura-admin-secret',
-    },
-  },
+  connectionParams: () => ({ headers: authHeaders() }),
 }) : null;
 
 export const urqlClient = createClient({
@@ -28,10 +25,6 @@ export const urqlClient = createClient({
     })] : []),
   ],
   fetchOptions: () => {
-    return {
-      headers: {
-        'x-hasura-admin-secret': process.env.NEXT_PUBLIC_HASURA_ADMIN_SECRET || 'hasura-admin-secret',
-      },
-    };
+    return { headers: authHeaders() };
   },
 });
\ No newline at end of file